@@ -47,6 +47,10 @@ class Reel {
   final String title;
   final String description;
   final String creationTimestamp;
+  final int likeCount;
+  final int saveCount;
+  final bool likedByMe;
+  final bool savedByMe;
 
   Reel({
     required this.id,
@@ -54,6 +58,10 @@ class Reel {
     required this.title,
     required this.description,
     required this.creationTimestamp,
+    this.likeCount = 0,
+    this.saveCount = 0,
+    this.likedByMe = false,
+    this.savedByMe = false,
   });
   factory Reel.fromJson(Map<String, dynamic> json) {
     if (json['id'] == null || json['id'].toString().isEmpty) {
@@ -70,6 +78,10 @@ class Reel {
       title: json['title']?.toString() ?? '',
       description: json['description']?.toString() ?? '',
       creationTimestamp: json['creation_timestamp']?.toString() ?? '',
+      likeCount: json['like_count'] is int ? json['like_count'] : 0,
+      saveCount: json['save_count'] is int ? json['save_count'] : 0,
+      likedByMe: json['liked_by_me'] == true,
+      savedByMe: json['saved_by_me'] == true,
     );
   }
 
@@ -80,12 +92,39 @@ class Reel {
       'title': title,
       'description': description,
       'creation_timestamp': creationTimestamp,
+      'like_count': likeCount,
+      'save_count': saveCount,
+      'liked_by_me': likedByMe,
+      'saved_by_me': savedByMe,
     };
   }
 
   bool get isValid => id.isNotEmpty && videoId.isNotEmpty;
 }
 
+class ReelEngagement {
+  final int likeCount;
+  final int saveCount;
+  final bool likedByMe;
+  final bool savedByMe;
+
+  ReelEngagement({
+    required this.likeCount,
+    required this.saveCount,
+    required this.likedByMe,
+    required this.savedByMe,
+  });
+
+  factory ReelEngagement.fromJson(Map<String, dynamic> json) {
+    return ReelEngagement(
+      likeCount: json['like_count'] is int ? json['like_count'] : 0,
+      saveCount: json['save_count'] is int ? json['save_count'] : 0,
+      likedByMe: json['liked_by_me'] == true,
+      savedByMe: json['saved_by_me'] == true,
+    );
+  }
+}
+
 class ReelWithVideo {
   final Reel reel;
   final Video video;
@@ -94,6 +133,20 @@ class ReelWithVideo {
     required this.video,
   });
 
+  factory ReelWithVideo.fromJson(Map<String, dynamic> json) {
+    if (json['reel'] is! Map<String, dynamic>) {
+      throw ArgumentError('Missing reel');
+    }
+    if (json['video'] is! Map<String, dynamic>) {
+      throw ArgumentError('Missing video');
+    }
+
+    return ReelWithVideo(
+      reel: Reel.fromJson(json['reel']),
+      video: Video.fromJson(json['video']),
+    );
+  }
+
   bool get isValid => reel.isValid && video.isValid;
 
   Map<String, dynamic> toJson() {
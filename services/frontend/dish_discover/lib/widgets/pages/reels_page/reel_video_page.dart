@@ -1,9 +1,10 @@
 import 'package:flutter/material.dart';
 import 'models.dart';
+import 'reels_service.dart';
 import 'video_player_widget.dart';
 import '../../style/style.dart';
 
-class ReelVideoPage extends StatelessWidget {
+class ReelVideoPage extends StatefulWidget {
   final Reel reel;
   final Video video;
 
@@ -13,8 +14,47 @@ class ReelVideoPage extends StatelessWidget {
     required this.video,
   });
 
+  @override
+  State<ReelVideoPage> createState() => _ReelVideoPageState();
+}
+
+class _ReelVideoPageState extends State<ReelVideoPage> {
+  late ReelEngagement engagement;
+  bool updating = false;
+
+  @override
+  void initState() {
+    super.initState();
+    engagement = ReelEngagement(
+      likeCount: widget.reel.likeCount,
+      saveCount: widget.reel.saveCount,
+      likedByMe: widget.reel.likedByMe,
+      savedByMe: widget.reel.savedByMe,
+    );
+  }
+
+  Future<void> _toggle(
+      Future<ReelEngagement> Function(String, bool) update, bool on) async {
+    if (updating) return;
+    setState(() => updating = true);
+    try {
+      final updated = await update(widget.reel.id, on);
+      if (mounted) setState(() => engagement = updated);
+    } catch (e) {
+      if (mounted) {
+        ScaffoldMessenger.of(context).showSnackBar(
+          const SnackBar(content: Text('Could not update the reel')),
+        );
+      }
+    } finally {
+      if (mounted) setState(() => updating = false);
+    }
+  }
+
   @override
   Widget build(BuildContext context) {
+    final reel = widget.reel;
+    final video = widget.video;
     final screenWidth = MediaQuery.of(context).size.width;
     final screenHeight = MediaQuery.of(context).size.height;
     final videoWidth = screenWidth * 0.7;
@@ -100,10 +140,14 @@ class ReelVideoPage extends StatelessWidget {
           child: Column(
             children: [
               _buildActionButton(
-                icon: Icons.favorite_border,
+                icon: engagement.likedByMe
+                    ? Icons.favorite
+                    : Icons.favorite_border,
                 color: likeColor,
-                onPressed: () {},
+                onPressed: () =>
+                    _toggle(ReelsService.setLiked, !engagement.likedByMe),
               ),
+              _buildCount(engagement.likeCount),
               const SizedBox(height: 12),
               _buildActionButton(
                 icon: Icons.comment,
@@ -112,10 +156,14 @@ class ReelVideoPage extends StatelessWidget {
               ),
               const SizedBox(height: 12),
               _buildActionButton(
-                icon: Icons.share,
+                icon: engagement.savedByMe
+                    ? Icons.bookmark
+                    : Icons.bookmark_border,
                 color: saveColor,
-                onPressed: () {},
+                onPressed: () =>
+                    _toggle(ReelsService.setSaved, !engagement.savedByMe),
               ),
+              _buildCount(engagement.saveCount),
             ],
           ),
         ),
@@ -123,6 +171,20 @@ class ReelVideoPage extends StatelessWidget {
     );
   }
 
+  Widget _buildCount(int count) {
+    return Padding(
+      padding: const EdgeInsets.only(top: 4),
+      child: Text(
+        '$count',
+        style: const TextStyle(
+          color: Colors.white,
+          fontSize: 12,
+          fontWeight: FontWeight.bold,
+        ),
+      ),
+    );
+  }
+
   Widget _buildActionButton({
     required IconData icon,
     required VoidCallback onPressed,
@@ -8,24 +8,9 @@ import '../../../services/api_client.dart';
 class ReelsService {
   static final ApiClient _apiClient = ApiClient();
 
-  static Map<String, Map<String, dynamic>> _createVideoMap(
-      List<dynamic> videosData) {
-    final Map<String, Map<String, dynamic>> videoMap = {};
-
-    for (final videoData in videosData) {
-      if (videoData != null &&
-          videoData is Map<String, dynamic> &&
-          videoData.containsKey('id')) {
-        videoMap[videoData['id'].toString()] = videoData;
-      }
-    }
-
-    return videoMap;
-  }
-
   static Future<List<ReelWithVideo>> fetchReelsWithVideos() async {
     try {
-      final response = await _apiClient.get('/api/reels/reel-videos');
+      final response = await _apiClient.get('/api/reels/feed');
 
       if (response.statusCode == 200) {
         if (response.data is! Map<String, dynamic>) {
@@ -34,45 +19,26 @@ class ReelsService {
         }
 
         final Map<String, dynamic> responseData = response.data;
-        if (!responseData.containsKey('reels') ||
-            responseData['reels'] is! List) {
-          throw Exception('Missing or invalid "reels" key in response');
+        if (!responseData.containsKey('items') ||
+            responseData['items'] is! List) {
+          throw Exception('Missing or invalid "items" key in response');
         }
+        final List<dynamic> itemsData = responseData['items'];
 
-        if (!responseData.containsKey('videos') ||
-            responseData['videos'] is! List) {
-          throw Exception('Missing or invalid "videos" key in response');
-        }
-        final List<dynamic> reelsData = responseData['reels'];
-        final List<dynamic> videosData = responseData['videos'];
-
-        final videoMap = _createVideoMap(videosData);
         List<ReelWithVideo> reelsWithVideos = [];
-        for (int i = 0; i < reelsData.length; i++) {
-          final reelJson = reelsData[i];
-
-          if (reelJson == null || reelJson is! Map<String, dynamic>) {
+        for (final itemJson in itemsData) {
+          if (itemJson == null || itemJson is! Map<String, dynamic>) {
             continue;
           }
 
           try {
-            final reel = Reel.fromJson(reelJson);
-            final videoJson = videoMap[reel.videoId];
-
-            if (videoJson != null) {
-              try {
-                final video = Video.fromJson(videoJson);
-                final reelWithVideo = ReelWithVideo(reel: reel, video: video);
-
-                if (reelWithVideo.isValid) {
-                  reelsWithVideos.add(reelWithVideo);
-                }
-              } catch (videoError) {
-                // Skip invalid videos
-              }
+            final reelWithVideo = ReelWithVideo.fromJson(itemJson);
+
+            if (reelWithVideo.isValid) {
+              reelsWithVideos.add(reelWithVideo);
             }
-          } catch (reelError) {
-            // Skip invalid reels
+          } catch (itemError) {
+            // Skip invalid items
           }
         }
 
@@ -86,6 +52,31 @@ class ReelsService {
     }
   }
 
+  /// Likes or unlikes the reel, returning its counters afterwards.
+  static Future<ReelEngagement> setLiked(String reelId, bool liked) {
+    return _setReaction('/api/reels/reel/$reelId/like', liked);
+  }
+
+  /// Saves or unsaves the reel, returning its counters afterwards.
+  static Future<ReelEngagement> setSaved(String reelId, bool saved) {
+    return _setReaction('/api/reels/reel/$reelId/save', saved);
+  }
+
+  static Future<ReelEngagement> _setReaction(String path, bool on) async {
+    try {
+      final response =
+          on ? await _apiClient.put(path) : await _apiClient.delete(path);
+
+      if (response.statusCode == 200 &&
+          response.data is Map<String, dynamic>) {
+        return ReelEngagement.fromJson(response.data);
+      }
+      throw Exception('Failed to update reel: ${response.statusCode}');
+    } catch (e) {
+      throw Exception('Failed to update reel: $e');
+    }
+  }
+
   static Future<bool> uploadReel({
     required List<int> fileBytes,
     required String fileName,
@@ -0,0 +1,87 @@
+use actix_web::{dev::Payload, http::header, web, FromRequest, HttpRequest};
+use futures_util::future::LocalBoxFuture;
+use uuid::Uuid;
+
+use crate::error::{error::AppError, problem::ErrorCode};
+
+pub mod jwt;
+
+pub use jwt::JwtValidator;
+
+/// May change or remove any content.
+pub const MODERATOR_ROLE: &str = "moderator";
+/// Everything a moderator may do, plus the `/admin` endpoints.
+pub const ADMIN_ROLE: &str = "admin";
+
+/// The caller of a request, taken from a validated bearer token.
+#[derive(Clone, Debug)]
+pub struct AuthenticatedUser {
+    /// The `sub` claim, the Keycloak user id.
+    pub id: Uuid,
+    /// Realm roles plus the roles of the configured client.
+    pub roles: Vec<String>,
+}
+
+impl AuthenticatedUser {
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r == role)
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.has_role(ADMIN_ROLE)
+    }
+
+    pub fn is_moderator(&self) -> bool {
+        self.has_role(MODERATOR_ROLE) || self.is_admin()
+    }
+}
+
+fn bearer_token(req: &HttpRequest) -> Result<String, AppError> {
+    let value = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .ok_or_else(|| AppError::Unauthorized(ErrorCode::Unauthorized, "Missing bearer token".into()))?
+        .to_str()
+        .map_err(|_| AppError::Unauthorized(ErrorCode::Unauthorized, "Invalid Authorization header".into()))?;
+
+    match value.split_once(' ') {
+        Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") && !token.trim().is_empty() => {
+            Ok(token.trim().to_string())
+        }
+        _ => Err(AppError::Unauthorized(ErrorCode::Unauthorized, "Authorization must be a bearer token".into())),
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = AppError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let validator = req.app_data::<web::Data<JwtValidator>>().cloned();
+        let token = bearer_token(req);
+
+        Box::pin(async move {
+            let validator = validator
+                .ok_or_else(|| AppError::InternalError("No token validator is configured".into()))?;
+            validator.validate(&token?).await
+        })
+    }
+}
+
+/// The caller when the request carries a bearer token, for endpoints that also serve anonymous users.
+/// A token that is present but invalid is still rejected.
+#[derive(Clone, Debug)]
+pub struct OptionalUser(pub Option<AuthenticatedUser>);
+
+impl FromRequest for OptionalUser {
+    type Error = AppError;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        if !req.headers().contains_key(header::AUTHORIZATION) {
+            return Box::pin(async { Ok(OptionalUser(None)) });
+        }
+        let user = AuthenticatedUser::from_request(req, payload);
+        Box::pin(async move { user.await.map(|user| OptionalUser(Some(user))) })
+    }
+}
@@ -15,10 +15,130 @@ pub struct DatabaseSettings {
     host: String,
 }
 
+#[derive(serde::Deserialize, Clone)]
+pub struct UploadSettings {
+    pub directory: String,
+    pub max_file_size_mb: u64,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct LocalStorageSettings {
+    pub directory: String,
+    pub public_url: String,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct S3StorageSettings {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+    pub public_url: Option<String>,
+}
+
+#[derive(serde::Deserialize, Clone)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum StorageSettings {
+    Local(LocalStorageSettings),
+    S3(S3StorageSettings),
+}
+
+#[derive(serde::Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    Jpg,
+    Webp,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct MediaSettings {
+    pub ffprobe_path: String,
+    pub ffmpeg_path: String,
+    /// Probing and frame extraction are killed when they run longer than this.
+    pub process_timeout_seconds: u64,
+    pub poster_format: ImageFormat,
+    pub poster_offset_seconds: f64,
+    pub poster_width: Option<u32>,
+    pub thumbnail_width: u32,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct RenditionSettings {
+    pub name: String,
+    pub height: u32,
+    pub video_bitrate_kbps: u32,
+    pub audio_bitrate_kbps: u32,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct TranscodeSettings {
+    pub segment_seconds: u32,
+    pub renditions: Vec<RenditionSettings>,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct JobSettings {
+    pub workers: usize,
+    pub poll_interval_ms: u64,
+    /// A running job not finished within this time is assumed lost and claimed again.
+    pub lease_seconds: i64,
+    pub max_attempts: i32,
+    pub backoff_base_seconds: i64,
+    pub backoff_max_seconds: i64,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct TusSettings {
+    /// Where received bytes are kept until the upload completes, must not be served to clients.
+    pub directory: String,
+    /// Unfinished uploads are removed this long after their last chunk.
+    pub expiration_hours: i64,
+    pub sweep_interval_seconds: u64,
+    /// Longest a PATCH keeps other requests off its upload, should it never release it.
+    pub lock_seconds: i64,
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct StatsSettings {
+    pub refresh_interval_seconds: u64,
+    /// Days rebuilt on every refresh besides today, to take in late events and removed likes.
+    pub lookback_days: i64,
+}
+
+/// Where the keys that sign access tokens are read from.
+#[derive(serde::Deserialize, Clone)]
+#[serde(tag = "source", rename_all = "lowercase")]
+pub enum JwksSource {
+    File { path: String },
+    Url { url: String },
+}
+
+#[derive(serde::Deserialize, Clone)]
+pub struct AuthSettings {
+    pub issuer: String,
+    /// A token is accepted when its `aud` contains any of these.
+    pub audience: Vec<String>,
+    /// Client whose roles under `resource_access` are granted along with the realm roles.
+    pub client_id: Option<String>,
+    pub leeway_seconds: u64,
+    /// Minimum time between reloads of the key set when a token names an unknown key.
+    pub jwks_refresh_seconds: u64,
+    pub jwks: JwksSource,
+}
+
 #[derive(serde::Deserialize)]
 pub struct Settings {
     pub app: AppSettings,
     pub database: DatabaseSettings,
+    pub upload: UploadSettings,
+    pub storage: StorageSettings,
+    pub media: MediaSettings,
+    pub transcoding: TranscodeSettings,
+    pub jobs: JobSettings,
+    pub tus: TusSettings,
+    pub stats: StatsSettings,
+    pub auth: AuthSettings,
 }
 
 // implement this function as settings method
@@ -38,6 +158,44 @@ impl DatabaseSettings {
         )
     }
 }
+impl MediaSettings {
+    pub fn process_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.process_timeout_seconds.max(1))
+    }
+}
+
+impl UploadSettings {
+    pub fn max_file_size_bytes(&self) -> u64 {
+        self.max_file_size_mb * 1024 * 1024
+    }
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpg => ".jpg",
+            ImageFormat::Webp => ".webp",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpg => "image/jpeg",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+}
+
+impl JobSettings {
+    /// Delay before the next attempt, doubling after every failure.
+    pub fn backoff_seconds(&self, attempts: i32) -> i64 {
+        let exponent = attempts.saturating_sub(1).clamp(0, 30) as u32;
+        self.backoff_base_seconds
+            .saturating_mul(2i64.saturating_pow(exponent))
+            .min(self.backoff_max_seconds)
+    }
+}
+
 //impl Config {
 //    pub fn from_file(path: &'static str) -> Self {
 //        let config = fs::read_to_string(path).unwrap();
@@ -52,3 +210,42 @@ impl DatabaseSettings {
 //        todo!()
 //    }
 //}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jobs(backoff_base_seconds: i64, backoff_max_seconds: i64) -> JobSettings {
+        JobSettings {
+            workers: 1,
+            poll_interval_ms: 1000,
+            lease_seconds: 3600,
+            max_attempts: 5,
+            backoff_base_seconds,
+            backoff_max_seconds,
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_after_every_attempt() {
+        let settings = jobs(10, 3600);
+        let delays: Vec<i64> = (1..=6).map(|attempts| settings.backoff_seconds(attempts)).collect();
+        assert_eq!(delays, vec![10, 20, 40, 80, 160, 320]);
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let settings = jobs(10, 3600);
+        assert_eq!(settings.backoff_seconds(9), 2560);
+        assert_eq!(settings.backoff_seconds(10), 3600);
+        assert_eq!(settings.backoff_seconds(1000), 3600);
+        assert_eq!(jobs(i64::MAX / 2, i64::MAX).backoff_seconds(i32::MAX), i64::MAX);
+    }
+
+    #[test]
+    fn backoff_before_the_first_attempt_is_the_base() {
+        let settings = jobs(10, 3600);
+        assert_eq!(settings.backoff_seconds(0), 10);
+        assert_eq!(settings.backoff_seconds(-3), 10);
+    }
+}
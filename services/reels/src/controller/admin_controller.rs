@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use actix_web::{get, post, web, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser, error::error::AppError, model::{JobStatus, MediaJob}, service::job_service::JobRepository, AppState
+};
+
+use super::log_request;
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_jobs_paginated);
+    cfg.service(retry_job);
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/jobs",
+    params(
+        ("status" = Option<JobStatus>, Query, description = "Only jobs in this status"),
+        ("kind" = Option<String>, Query, description = "Only jobs of this kind (transcode, thumbnail, cleanup)"),
+        ("page" = Option<u32>, Query, description = "Page number (default: 1)"),
+        ("limit" = Option<u32>, Query, description = "Items per page (default: 20, at most 50)")
+    ),
+    responses(
+        (status = 200, description = "List of background jobs, newest first", body = [MediaJob]),
+        (status = 400, description = "Unknown status"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Admin"
+)]
+#[get("/admin/jobs")]
+async fn get_jobs_paginated(
+    user: AuthenticatedUser,
+    web::Query(params): web::Query<HashMap<String, String>>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /admin/jobs", &app_state.connections);
+
+    let status = params.get("status").cloned();
+    let kind = params.get("kind").cloned();
+    let page = params
+        .get("page")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(1);
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(20);
+
+    let jobs = app_state
+        .job_service
+        .get_jobs_paginated(&user, status, kind, page, limit)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(jobs))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/jobs/{id}/retry",
+    params(
+        ("id" = Uuid, Path, description = "ID of the job to retry")
+    ),
+    responses(
+        (status = 200, description = "Job queued to run now", body = MediaJob),
+        (status = 400, description = "Job is running or already succeeded"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 404, description = "Job not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    description = r#"
+Requeue a dead job, or run a job waiting for its backoff right away. Attempts start over.
+    "#,
+    tag = "Admin"
+)]
+#[post("/admin/jobs/{id}/retry")]
+async fn retry_job(
+    user: AuthenticatedUser,
+    job_id: web::Path<Uuid>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Post: /admin/jobs/{id}/retry", &app_state.connections);
+
+    let job = app_state
+        .job_service
+        .retry_job(&user, job_id.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(job))
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use actix_web::{delete, get, http::header, post, put, web, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser, error::{error::AppError, problem::ErrorCode}, model::{Comment, CommentPage, CommentSort, PostComment, PutComment}, service::comment_service::CommentRepository, AppState
+};
+
+use super::log_request;
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_comments);
+    cfg.service(post_comment);
+    cfg.service(get_comment_by_id);
+    cfg.service(get_replies);
+    cfg.service(put_comment);
+    cfg.service(delete_comment);
+}
+
+#[utoipa::path(
+    get,
+    path = "/reel/{id}/comments",
+    params(
+        ("id" = Uuid, Path, description = "Reel UUID"),
+        ("sort" = Option<String>, Query, description = "`newest` (default) or `top`, most replied to first"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<u32>, Query, description = "Items per page (default: 10, at most 50)")
+    ),
+    responses(
+        (status = 200, description = "Page of the comments on the reel, without replies", body = CommentPage),
+        (status = 400, description = "Invalid sort or cursor"),
+        (status = 404, description = "Reel not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Comments"
+)]
+#[get("/reel/{id}/comments")]
+async fn get_comments(
+    reel_id: web::Path<Uuid>,
+    web::Query(params): web::Query<HashMap<String, String>>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /reel/{id}/comments", &app_state.connections);
+
+    let sort = params
+        .get("sort")
+        .map(|s| s.parse::<CommentSort>())
+        .transpose()
+        .map_err(|e| AppError::BadRequest(ErrorCode::InvalidQuery, e))?
+        .unwrap_or_default();
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(10);
+
+    let comments = app_state
+        .comment_service
+        .get_comments(reel_id.into_inner(), sort, params.get("cursor").cloned(), limit)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(comments))
+}
+
+#[utoipa::path(
+    post,
+    path = "/reel/{id}/comments",
+    params(
+        ("id" = Uuid, Path, description = "Reel UUID")
+    ),
+    request_body = PostComment,
+    responses(
+        (status = 201, description = "Comment created, its URL is in the Location header", body = Comment),
+        (status = 400, description = "Empty or too long body, or a parent that cannot be replied to"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 404, description = "Reel not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Comments"
+)]
+#[post("/reel/{id}/comments")]
+async fn post_comment(
+    user: AuthenticatedUser,
+    reel_id: web::Path<Uuid>,
+    comment: web::Json<PostComment>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Post: /reel/{id}/comments", &app_state.connections);
+
+    let comment = app_state
+        .comment_service
+        .post_comment(reel_id.into_inner(), comment.into_inner(), &user)
+        .await?;
+
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/comment/{}", comment.id)))
+        .json(comment))
+}
+
+#[utoipa::path(
+    get,
+    path = "/comment/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Comment UUID")
+    ),
+    responses(
+        (status = 200, description = "Comment found", body = Comment),
+        (status = 404, description = "Comment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Comments"
+)]
+#[get("/comment/{id}")]
+async fn get_comment_by_id(
+    comment_id: web::Path<Uuid>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /comment/{id}", &app_state.connections);
+
+    let comment = app_state
+        .comment_service
+        .get_comment_by_id(comment_id.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(comment))
+}
+
+#[utoipa::path(
+    get,
+    path = "/comment/{id}/replies",
+    params(
+        ("id" = Uuid, Path, description = "Comment UUID"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<u32>, Query, description = "Items per page (default: 10, at most 50)")
+    ),
+    responses(
+        (status = 200, description = "Page of the replies to the comment, oldest first", body = CommentPage),
+        (status = 400, description = "Invalid cursor"),
+        (status = 404, description = "Comment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Comments"
+)]
+#[get("/comment/{id}/replies")]
+async fn get_replies(
+    comment_id: web::Path<Uuid>,
+    web::Query(params): web::Query<HashMap<String, String>>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /comment/{id}/replies", &app_state.connections);
+
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(10);
+
+    let replies = app_state
+        .comment_service
+        .get_replies(comment_id.into_inner(), params.get("cursor").cloned(), limit)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(replies))
+}
+
+#[utoipa::path(
+    put,
+    path = "/comment/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Comment UUID")
+    ),
+    request_body = PutComment,
+    responses(
+        (status = 200, description = "Comment edited", body = Comment),
+        (status = 400, description = "Empty or too long body"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Written by another user"),
+        (status = 404, description = "Comment not found or deleted"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Comments"
+)]
+#[put("/comment/{id}")]
+async fn put_comment(
+    user: AuthenticatedUser,
+    comment_id: web::Path<Uuid>,
+    comment: web::Json<PutComment>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Put: /comment/{id}", &app_state.connections);
+
+    let comment = app_state
+        .comment_service
+        .put_comment(comment_id.into_inner(), comment.into_inner(), &user)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(comment))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/comment/{id}",
+    params(
+        ("id" = Uuid, Path, description = "Comment UUID")
+    ),
+    responses(
+        (status = 204, description = "Comment deleted, or it already was"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Caller is neither the author, the reel owner nor a moderator"),
+        (status = 404, description = "Comment not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    description = r#"
+The comment stays listed with an empty body and `deleted_at` set, so its replies keep their parent.
+    "#,
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Comments"
+)]
+#[delete("/comment/{id}")]
+async fn delete_comment(
+    user: AuthenticatedUser,
+    comment_id: web::Path<Uuid>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Delete: /comment/{id}", &app_state.connections);
+
+    app_state
+        .comment_service
+        .delete_comment(comment_id.into_inner(), &user)
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
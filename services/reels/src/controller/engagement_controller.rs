@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use actix_web::{delete, get, put, web, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser, error::error::AppError, model::{ReelEngagement, ReelPage}, service::engagement_service::EngagementRepository, AppState
+};
+
+use super::log_request;
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(like_reel);
+    cfg.service(unlike_reel);
+    cfg.service(save_reel);
+    cfg.service(unsave_reel);
+    cfg.service(get_saved_reels);
+}
+
+#[utoipa::path(
+    put,
+    path = "/reel/{id}/like",
+    params(
+        ("id" = Uuid, Path, description = "Reel UUID")
+    ),
+    responses(
+        (status = 200, description = "Reel liked, liking it again changes nothing", body = ReelEngagement),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 404, description = "Reel not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Engagement"
+)]
+#[put("/reel/{id}/like")]
+async fn like_reel(
+    user: AuthenticatedUser,
+    reel_id: web::Path<Uuid>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Put: /reel/{id}/like", &app_state.connections);
+
+    let engagement = app_state
+        .engagement_service
+        .like_reel(&user, reel_id.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(engagement))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/reel/{id}/like",
+    params(
+        ("id" = Uuid, Path, description = "Reel UUID")
+    ),
+    responses(
+        (status = 200, description = "Like removed, or there was none", body = ReelEngagement),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 404, description = "Reel not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Engagement"
+)]
+#[delete("/reel/{id}/like")]
+async fn unlike_reel(
+    user: AuthenticatedUser,
+    reel_id: web::Path<Uuid>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Delete: /reel/{id}/like", &app_state.connections);
+
+    let engagement = app_state
+        .engagement_service
+        .unlike_reel(&user, reel_id.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(engagement))
+}
+
+#[utoipa::path(
+    put,
+    path = "/reel/{id}/save",
+    params(
+        ("id" = Uuid, Path, description = "Reel UUID")
+    ),
+    responses(
+        (status = 200, description = "Reel saved, saving it again changes nothing", body = ReelEngagement),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 404, description = "Reel not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Engagement"
+)]
+#[put("/reel/{id}/save")]
+async fn save_reel(
+    user: AuthenticatedUser,
+    reel_id: web::Path<Uuid>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Put: /reel/{id}/save", &app_state.connections);
+
+    let engagement = app_state
+        .engagement_service
+        .save_reel(&user, reel_id.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(engagement))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/reel/{id}/save",
+    params(
+        ("id" = Uuid, Path, description = "Reel UUID")
+    ),
+    responses(
+        (status = 200, description = "Reel removed from the saved ones, or it was not saved", body = ReelEngagement),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 404, description = "Reel not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Engagement"
+)]
+#[delete("/reel/{id}/save")]
+async fn unsave_reel(
+    user: AuthenticatedUser,
+    reel_id: web::Path<Uuid>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Delete: /reel/{id}/save", &app_state.connections);
+
+    let engagement = app_state
+        .engagement_service
+        .unsave_reel(&user, reel_id.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(engagement))
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/saved-reels",
+    params(
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<u32>, Query, description = "Items per page (default: 10, at most 50)")
+    ),
+    responses(
+        (status = 200, description = "Page of the reels the user saved, most recently saved first", body = ReelPage),
+        (status = 400, description = "Invalid cursor"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Engagement"
+)]
+#[get("/user/saved-reels")]
+async fn get_saved_reels(
+    user: AuthenticatedUser,
+    web::Query(params): web::Query<HashMap<String, String>>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /user/saved-reels", &app_state.connections);
+
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(10);
+
+    let reels = app_state
+        .engagement_service
+        .get_saved_reels(&user, params.get("cursor").cloned(), limit)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(reels))
+}
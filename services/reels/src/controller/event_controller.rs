@@ -0,0 +1,48 @@
+use actix_web::{post, web, HttpResponse, Responder};
+
+use crate::{
+    auth::OptionalUser, error::error::AppError, model::{EventReceipt, PostReelEvents}, service::event_service::EventRepository, AppState
+};
+
+use super::log_request;
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_events);
+}
+
+#[utoipa::path(
+    post,
+    path = "/events",
+    request_body = PostReelEvents,
+    responses(
+        (status = 200, description = "Events stored and counted", body = EventReceipt),
+        (status = 400, description = "Invalid session id, too many events, or an event that is invalid or too old"),
+        (status = 401, description = "Invalid bearer token"),
+        (status = 500, description = "Internal server error")
+    ),
+    description = r#"
+Reports what was watched, in batches of at most 100 events. Events of one viewer on a reel less than
+30 minutes apart make up one view; the viewer is the signed in user, or the session for anonymous clients.
+Views and the share of each video watched are added to `view_count` and `completion_rate` of the reels.
+    "#,
+    security(
+        (),
+        ("bearer_auth" = [])
+    ),
+    tag = "Events"
+)]
+#[post("/events")]
+async fn post_events(
+    OptionalUser(viewer): OptionalUser,
+    batch: web::Json<PostReelEvents>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Post: /events", &app_state.connections);
+
+    let receipt = app_state
+        .event_service
+        .post_events(viewer.as_ref(), batch.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(receipt))
+}
@@ -1,11 +1,35 @@
 use std::sync::Mutex;
 
+pub mod admin_controller;
+pub use admin_controller::init as init_admin_controller;
+
+pub mod comment_controller;
+pub use comment_controller::init as init_comment_controller;
+
+pub mod engagement_controller;
+pub use engagement_controller::init as init_engagement_controller;
+
+pub mod recipe_controller;
+pub use recipe_controller::init as init_recipe_controller;
+
 pub mod reel_controller;
 pub use reel_controller::init as init_reel_controller;
 
+pub mod event_controller;
+pub use event_controller::init as init_event_controller;
+
 pub mod health_controller;
 pub use health_controller::init as init_health_controller;
 
+pub mod stats_controller;
+pub use stats_controller::init as init_stats_controller;
+
+pub mod tag_controller;
+pub use tag_controller::init as init_tag_controller;
+
+pub mod upload_controller;
+pub use upload_controller::init as init_upload_controller;
+
 pub mod video_controller;
 pub use video_controller::init as init_video_controller;
 
@@ -0,0 +1,111 @@
+use actix_web::{delete, get, put, web, HttpResponse, Responder};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser, error::error::AppError, model::Video, service::video_service::VideoRepository, AppState
+};
+
+use super::log_request;
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_videos_by_recipe_id);
+    cfg.service(put_recipe_video);
+    cfg.service(delete_recipe_video);
+}
+
+#[utoipa::path(
+    get,
+    path = "/recipe/{id}/videos",
+    params(
+        ("id" = Uuid, Path, description = "ID of the recipe")
+    ),
+    responses(
+        (status = 200, description = "Videos showing the recipe, in the order they were linked", body = [Video]),
+        (status = 404, description = "Recipe not found")
+    ),
+    tag = "Recipes"
+)]
+#[get("/recipe/{id}/videos")]
+async fn get_videos_by_recipe_id(
+    recipe_id: web::Path<Uuid>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /recipe/{id}/videos", &app_state.connections);
+
+    let videos = app_state
+        .video_service
+        .get_videos_by_recipe_id(recipe_id.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(videos))
+}
+
+#[utoipa::path(
+    put,
+    path = "/recipe/{recipe_id}/videos/{video_id}",
+    params(
+        ("recipe_id" = Uuid, Path, description = "ID of the recipe"),
+        ("video_id" = Uuid, Path, description = "ID of the video to show on the recipe")
+    ),
+    responses(
+        (status = 204, description = "Video linked to the recipe, also when it already was"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Video is owned by another user and caller is not a moderator"),
+        (status = 404, description = "Recipe or video not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Recipes"
+)]
+#[put("/recipe/{recipe_id}/videos/{video_id}")]
+async fn put_recipe_video(
+    user: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Put: /recipe/{recipe_id}/videos/{video_id}", &app_state.connections);
+
+    let (recipe_id, video_id) = path.into_inner();
+    app_state
+        .video_service
+        .attach_recipe(video_id, recipe_id, &user)
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/recipe/{recipe_id}/videos/{video_id}",
+    params(
+        ("recipe_id" = Uuid, Path, description = "ID of the recipe"),
+        ("video_id" = Uuid, Path, description = "ID of the video to remove from the recipe")
+    ),
+    responses(
+        (status = 204, description = "Video no longer shown on the recipe"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Video is owned by another user and caller is not a moderator"),
+        (status = 404, description = "Video not found or not linked to the recipe")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Recipes"
+)]
+#[delete("/recipe/{recipe_id}/videos/{video_id}")]
+async fn delete_recipe_video(
+    user: AuthenticatedUser,
+    path: web::Path<(Uuid, Uuid)>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Delete: /recipe/{recipe_id}/videos/{video_id}", &app_state.connections);
+
+    let (recipe_id, video_id) = path.into_inner();
+    app_state
+        .video_service
+        .detach_recipe(video_id, recipe_id, &user)
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
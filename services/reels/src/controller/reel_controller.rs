@@ -1,11 +1,12 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path};
 
 use crate::{
-    error::error::AppError, model::{PostReel, PostVideo, Reel, ReelWithVideosForm}, service::{reel_service::ReelRepository, video_service::VideoRepository}, util::read_bytes::read_bytes, AppState
+    auth::{AuthenticatedUser, OptionalUser},
+    error::{error::AppError, problem::ErrorCode}, model::{PostReel, PostVideo, Reel, ReelFeed, ReelSearch, ReelSearchPage, ReelWithVideo, ReelWithVideos, ReelWithVideosForm}, service::{engagement_service::EngagementRepository, reel_service::ReelRepository}, util::{read_bytes::read_bytes, stream_to_file::{stream_to_file, TempUpload}}, AppState
 };
 use actix_multipart::{Field, Multipart};
-use actix_web::{delete, get, post, put, web, HttpResponse, Responder, HttpRequest};
-use bytes::BytesMut;
+use actix_web::{delete, get, http::header, post, put, web, HttpResponse, Responder};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde_json::from_slice;
 use uuid::Uuid;
 use futures_util::StreamExt as _;
@@ -13,9 +14,12 @@ use futures_util::StreamExt as _;
 use super::log_request;
 
 pub fn init(cfg: &mut web::ServiceConfig) {
+    // before /reel/{id}, which would otherwise reject "search" as an invalid id
+    cfg.service(search_reels);
     cfg.service(get_reel_by_id);
     cfg.service(get_reels_paginated);
     cfg.service(get_reels_with_videos_paginated);
+    cfg.service(get_feed);
     cfg.service(get_reels_by_user_id);
     cfg.service(post_reel);
     cfg.service(post_reel_with_video);
@@ -23,6 +27,87 @@ pub fn init(cfg: &mut web::ServiceConfig) {
     cfg.service(delete_reel_with_video);
 }
 
+/// Reads an RFC 3339 timestamp, or a date meaning its midnight UTC.
+fn parse_time(params: &HashMap<String, String>, key: &str) -> Result<Option<NaiveDateTime>, AppError> {
+    let Some(value) = params.get(key) else {
+        return Ok(None);
+    };
+    if let Ok(time) = value.parse::<DateTime<Utc>>() {
+        return Ok(Some(time.naive_utc()));
+    }
+    value
+        .parse::<NaiveDate>()
+        .map(|date| Some(date.and_time(Default::default())))
+        .map_err(|_| AppError::BadRequest(ErrorCode::InvalidQuery, format!("{} must be an RFC 3339 timestamp or a date", key)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/reel/search",
+    params(
+        ("q" = String, Query, description = "Words to look for in titles and descriptions; the last one may be unfinished"),
+        ("user_id" = Option<Uuid>, Query, description = "Only reels posted by this user"),
+        ("from" = Option<String>, Query, description = "Only reels created at or after this time (RFC 3339 or date)"),
+        ("to" = Option<String>, Query, description = "Only reels created before this time (RFC 3339 or date)"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<u32>, Query, description = "Items per page (default: 10, at most 50)")
+    ),
+    responses(
+        (status = 200, description = "Matching reels, most relevant first", body = ReelSearchPage),
+        (status = 400, description = "Missing query, invalid filter or cursor"),
+        (status = 401, description = "Invalid bearer token"),
+        (status = 500, description = "Internal server error")
+    ),
+    description = r#"
+Every word has to appear in the title or description, as a whole word or the start of one.
+Title matches rank higher. `title_highlight` and `description_highlight` wrap the matches in `<mark>` tags.
+    "#,
+    security(
+        (),
+        ("bearer_auth" = [])
+    ),
+    tag = "Reels"
+)]
+#[get("/reel/search")]
+async fn search_reels(
+    OptionalUser(viewer): OptionalUser,
+    web::Query(params): web::Query<HashMap<String, String>>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /reel/search", &app_state.connections);
+
+    let query = params
+        .get("q")
+        .cloned()
+        .ok_or_else(|| AppError::BadRequest(ErrorCode::InvalidQuery, "Missing q".into()))?;
+    let posting_user_id = params
+        .get("user_id")
+        .map(|s| s.parse::<Uuid>())
+        .transpose()
+        .map_err(|_| AppError::BadRequest(ErrorCode::InvalidQuery, "user_id must be a UUID".into()))?;
+    let search = ReelSearch {
+        query,
+        posting_user_id,
+        from: parse_time(&params, "from")?,
+        to: parse_time(&params, "to")?,
+    };
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(10);
+
+    let mut results = app_state
+        .reels_service
+        .search_reels(search, params.get("cursor").cloned(), limit)
+        .await?;
+    app_state
+        .engagement_service
+        .mark_reels(viewer.as_ref(), results.results.iter_mut().map(|hit| &mut hit.reel).collect())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(results))
+}
+
 #[utoipa::path(
     get,
     path = "/reel/{id}",
@@ -31,19 +116,29 @@ pub fn init(cfg: &mut web::ServiceConfig) {
     ),
     responses(
         (status = 200, description = "Reel found", body = Reel),
+        (status = 401, description = "Invalid bearer token"),
         (status = 404, description = "Reel not found")
     ),
+    security(
+        (),
+        ("bearer_auth" = [])
+    ),
     tag = "Reels"
 )]
 #[get("/reel/{id}")]
 async fn get_reel_by_id(
+    OptionalUser(viewer): OptionalUser,
     reel_id: web::Path<Uuid>,
     app_state: web::Data<AppState<'_>>,
 ) -> Result<impl Responder, AppError> {
-    let reel =  app_state
+    let mut reel = app_state
         .reels_service
         .get_reel_by_id(reel_id.into_inner())
         .await?;
+    app_state
+        .engagement_service
+        .mark_reels(viewer.as_ref(), vec![&mut reel])
+        .await?;
 
     Ok(HttpResponse::Ok().json(reel))
 }
@@ -52,36 +147,60 @@ async fn get_reel_by_id(
     get,
     path = "/reel",
     params(
-        ("page" = Option<u32>, Query, description = "Page number (default: 1)"),
-        ("limit" = Option<u32>, Query, description = "Items per page (default: 10)")
+        ("page" = Option<u32>, Query, description = "Page number (default: 1), ignored when `cursor` is given"),
+        ("limit" = Option<u32>, Query, description = "Items per page (default: 10, at most 50)"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page; pass it empty for the first page. Switches the response to a `ReelPage`")
     ),
     responses(
-        (status = 200, description = "List of paginated reels", body = [Reel]),
+        (status = 200, description = "Reels, newest first; a `ReelPage` when `cursor` is given", body = [Reel]),
+        (status = 400, description = "Invalid cursor"),
+        (status = 401, description = "Invalid bearer token"),
         (status = 500, description = "Internal server error")
     ),
+    security(
+        (),
+        ("bearer_auth" = [])
+    ),
     tag = "Reels"
 )]
 #[get("/reel")]
 async fn get_reels_paginated(
+    OptionalUser(viewer): OptionalUser,
     web::Query(params): web::Query<HashMap<String, String>>,
     app_state: web::Data<AppState<'_>>,
 ) -> Result<impl Responder, AppError> {
     log_request("Get: /reel", &app_state.connections);
 
-    let page = params
-        .get("page")
-        .and_then(|s| s.parse::<u32>().ok())
-        .unwrap_or(1);
     let limit = params
         .get("limit")
         .and_then(|s| s.parse::<u32>().ok())
         .unwrap_or(10);
 
-    let reels = app_state
+    if let Some(cursor) = params.get("cursor") {
+        let mut reels = app_state
+            .reels_service
+            .get_reels_after_cursor((!cursor.is_empty()).then(|| cursor.clone()), limit)
+            .await?;
+        app_state
+            .engagement_service
+            .mark_reels(viewer.as_ref(), reels.reels.iter_mut().collect())
+            .await?;
+        return Ok(HttpResponse::Ok().json(reels));
+    }
+
+    let page = params
+        .get("page")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(1);
+    let mut reels = app_state
         .reels_service
         .get_reels_paginated(page, limit)
         .await?;
-    
+    app_state
+        .engagement_service
+        .mark_reels(viewer.as_ref(), reels.iter_mut().collect())
+        .await?;
+
     Ok(HttpResponse::Ok().json(reels))
 }
 
@@ -89,11 +208,13 @@ async fn get_reels_paginated(
     get,
     path = "/reel-videos",
     params(
-        ("page" = Option<u32>, Query, description = "Page number (default: 1)"),
-        ("limit" = Option<u32>, Query, description = "Items per page (default: 10)")
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<u32>, Query, description = "Items per page (default: 10, at most 50)"),
+        ("page" = Option<u32>, Query, description = "Deprecated: page number, `next_cursor` is then always null")
     ),
     responses(
-        (status = 200, description = "List of paginated reels with videos"),
+        (status = 200, description = "Page of reels with their videos, newest first", body = ReelWithVideos),
+        (status = 400, description = "Invalid cursor"),
         (status = 500, description = "Internal server error"),
     ),
     tag = "Reels"
@@ -105,20 +226,26 @@ async fn get_reels_with_videos_paginated(
 ) -> Result<impl Responder, AppError> {
     log_request("Get: /reel/videos", &app_state.connections);
 
-    let page = params
-        .get("page")
-        .and_then(|s| s.parse::<u32>().ok())
-        .unwrap_or(1);
     let limit = params
         .get("limit")
         .and_then(|s| s.parse::<u32>().ok())
         .unwrap_or(10);
 
-    let reels_with_videos = app_state
-        .reels_service
-        .get_reels_with_videos_paginated(page, limit)
-        .await?;
-    
+    let reels_with_videos = match params.get("page") {
+        Some(page) => {
+            app_state
+                .reels_service
+                .get_reels_with_videos_paginated(page.parse::<u32>().unwrap_or(1), limit)
+                .await?
+        }
+        None => {
+            app_state
+                .reels_service
+                .get_reels_with_videos_after_cursor(params.get("cursor").cloned(), limit)
+                .await?
+        }
+    };
+
     Ok(HttpResponse::Ok().json(reels_with_videos))
 }
 
@@ -127,35 +254,35 @@ async fn get_reels_with_videos_paginated(
     path = "/reel",
     request_body = PostReel,
     responses(
-        (status = 201, description = "Reel created successfully"),
-        (status = 400, description = "Bad request - Missing or invalid x-uuid header"),
+        (status = 201, description = "Reel created successfully, its URL is in the Location header", body = Reel),
+        (status = 400, description = "Bad request - Invalid input or missing video_id"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "video_id belongs to another user"),
         (status = 500, description = "Internal server error")
     ),
     security(
-        ("x-uuid" = [])
+        ("bearer_auth" = [])
     ),
     tag = "Reels"
 )]
 #[post("/reel")]
 async fn post_reel(
-    req: HttpRequest,
+    user: AuthenticatedUser,
     reel: web::Json<PostReel>,
     app_state: web::Data<AppState<'_>>,
 ) -> Result<impl Responder, AppError> {
     log_request("Post: /reel", &app_state.connections);
 
-    let posting_user_id = req
-        .headers()
-        .get("x-uuid")
-        .and_then(|h| h.to_str().ok())
-        .ok_or_else(|| AppError::BadRequest("Missing x-uuid header".into()))?
-        .parse::<Uuid>()
-        .map_err(|_| AppError::BadRequest("Invalid UUID format in x-uuid header".into()))?;
-
-    match app_state.reels_service.post_reel(reel.into_inner(), posting_user_id, None).await {
-        Ok(_) => Ok(HttpResponse::Created().finish()),
-        Err(_) => Ok(HttpResponse::InternalServerError().finish()),
-    }
+    let posting_user_id = user.id;
+
+    let reel = app_state
+        .reels_service
+        .post_reel(reel.into_inner(), posting_user_id, None)
+        .await?;
+
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/reel/{}", reel.id)))
+        .json(reel))
 }
 
 #[utoipa::path(
@@ -166,111 +293,165 @@ async fn post_reel(
         content_type = "multipart/form-data"
     ),
     responses(
-        (status = 200, description = "Video uploaded successfully"),
-        (status = 400, description = "Bad request - Missing or invalid x-uuid header, or invalid input")
+        (status = 201, description = "Reel and video created, the reel URL is in the Location header", body = ReelWithVideo),
+        (status = 400, description = "Bad request - Invalid input"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 500, description = "Internal server error")
     ),
     security(
-        ("x-uuid" = [])
+        ("bearer_auth" = [])
     ),
     description = r#"
-Upload a reel with video. Requires x-uuid header containing the posting user ID.
+Upload a reel with video. Requires a bearer token; the reel is posted as the user it was issued to.
+An optional `cover` image is used as the poster instead of a frame of the video.
     "#,
     tag = "Reels"
 )]
 #[post("/reel-video")]
 async fn post_reel_with_video(
-    req: HttpRequest,
+    user: AuthenticatedUser,
     mut payload: Multipart,
     app_state: web::Data<AppState<'_>>,
 ) -> impl Responder {
     log_request("Post: /reel-video", &app_state.connections);
 
-    let posting_user_id = match req
-        .headers()
-        .get("x-uuid")
-        .and_then(|h| h.to_str().ok())
-        .ok_or_else(|| AppError::BadRequest("Missing x-uuid header".into()))
-        .and_then(|uuid_str| uuid_str.parse::<Uuid>()
-            .map_err(|_| AppError::BadRequest("Invalid UUID format in x-uuid header".into())))
-    {
-        Ok(uuid) => uuid,
-        Err(e) => return Err(e),
-    };
+    let posting_user_id = user.id;
 
     let mut video_metadata: Option<PostVideo> = None;
     let mut reel_metadata: Option<PostReel> = None;
-    let mut video_data: Option<BytesMut> = None;
-    let mut file_name: Option<String> = None;
+    let mut video_data: Option<TempUpload> = None;
+    let mut cover_data: Option<TempUpload> = None;
 
     while let Some(item) = payload.next().await {
         let mut field: Field = item
-            .map_err(|_| AppError::BadRequest("Invalid multipart file".into()))?;
+            .map_err(|_| AppError::BadRequest(ErrorCode::InvalidMultipart, "Invalid multipart file".into()))?;
 
         let disposition = field.content_disposition();
         let name = disposition.and_then(|d| d.get_name()).unwrap_or("");
 
         match name {
             "file" => {
-                file_name = disposition.and_then(|d| d.get_filename().map(str::to_string));
-                video_data = Some(read_bytes(&mut field).await?);
+                let file_name = disposition
+                    .and_then(|d| d.get_filename().map(str::to_string))
+                    .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing file name".into()))?;
+                let upload = &app_state.video_service.upload;
+                video_data = Some(
+                    stream_to_file(
+                        &mut field,
+                        Path::new(&upload.directory),
+                        file_name,
+                        upload.max_file_size_bytes(),
+                    )
+                    .await?,
+                );
+            }
+            "cover" => {
+                let file_name = disposition
+                    .and_then(|d| d.get_filename().map(str::to_string))
+                    .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing cover file name".into()))?;
+                let upload = &app_state.video_service.upload;
+                cover_data = Some(
+                    stream_to_file(
+                        &mut field,
+                        Path::new(&upload.directory),
+                        file_name,
+                        upload.max_file_size_bytes(),
+                    )
+                    .await?,
+                );
             }
             "video" => {
                 let json_bytes = read_bytes(&mut field).await?;
                 video_metadata = Some(
                     from_slice(&json_bytes)
-                        .map_err(|_| AppError::BadRequest("Invalid JSON in 'video'".into()))?,
+                        .map_err(|_| AppError::BadRequest(ErrorCode::InvalidJson, "Invalid JSON in 'video'".into()))?,
                 );
             }
             "reel" => {
                 let json_bytes = read_bytes(&mut field).await?;
                 reel_metadata = Some(
                     from_slice(&json_bytes)
-                        .map_err(|_| AppError::BadRequest("Invalid JSON in 'reel'".into()))?,
+                        .map_err(|_| AppError::BadRequest(ErrorCode::InvalidMultipart, "Invalid JSON in 'reel'".into()))?,
                 );
             }
-            _ => return Err(AppError::BadRequest("Invalid multipart file".into()))
+            _ => return Err(AppError::BadRequest(ErrorCode::InvalidMultipart, "Invalid multipart file".into()))
         }
     }
 
     let video_metadata = video_metadata
-        .ok_or_else(|| AppError::BadRequest("Missing video metadata".into()))?;
+        .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing video metadata".into()))?;
     let reel_metadata = reel_metadata
-        .ok_or_else(|| AppError::BadRequest("Missing reel metadata".into()))?;
+        .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing reel metadata".into()))?;
     let video_data = video_data
-        .ok_or_else(|| AppError::BadRequest("Missing file field".into()))?;
-    let file_name = file_name
-        .ok_or_else(|| AppError::BadRequest("Missing file name".into()))?;    let video_id = app_state
-        .video_service
-        .post_video(video_metadata, posting_user_id, video_data, file_name)
-        .await?;    app_state
+        .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing file field".into()))?;
+
+    let created = app_state
         .reels_service
-        .post_reel(reel_metadata, posting_user_id, Some(video_id))
+        .post_reel_with_video(reel_metadata, video_metadata, posting_user_id, video_data, cover_data)
         .await?;
-    
-    Ok(HttpResponse::Ok().finish())
+
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/reel/{}", created.reel.id)))
+        .json(created))
 }
 
+#[utoipa::path(
+    put,
+    path = "/reel/{id}",
+    params(
+        ("id" = Uuid, Path, description = "ID of the reel to update")
+    ),
+    request_body = PostReel,
+    responses(
+        (status = 200, description = "Reel updated successfully", body = Reel),
+        (status = 400, description = "Bad request - Unknown video"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Reel is owned by another user and caller is not a moderator"),
+        (status = 404, description = "Reel not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Reels"
+)]
 #[put("/reel/{id}")]
-async fn put_reel(reel_id: web::Path<Uuid>, app_state: web::Data<AppState<'_>>) -> impl Responder {
+async fn put_reel(
+    user: AuthenticatedUser,
+    reel_id: web::Path<Uuid>,
+    reel: web::Json<PostReel>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
     log_request("Put: /reel/{id}", &app_state.connections);
-    HttpResponse::NotImplemented()
+
+    let reel = app_state
+        .reels_service
+        .put_reel(reel_id.into_inner(), reel.into_inner(), &user)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(reel))
 }
 
 #[utoipa::path(
     delete,
     path = "/reel/{id}",
     responses(
-        (status = 200, description = "Reel and video successfully deleted", body = String),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Owned by another user and caller is not a moderator"),
+        (status = 200, description = "The deleted reel; its video was deleted with it", body = Reel),
         (status = 404, description = "Reel not found"),
         (status = 500, description = "Internal Server Error")
     ),
     params(
         ("id" = Uuid, Path, description = "The unique ID of the reel to be deleted")
     ),
-    tag="Reels"
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Reels"
 )]
 #[delete("/reel/{id}")]
 async fn delete_reel_with_video(
+    user: AuthenticatedUser,
     reel_id: web::Path<Uuid>,
     app_state: web::Data<AppState<'_>>,
 ) -> Result<impl Responder, AppError> {
@@ -278,56 +459,117 @@ async fn delete_reel_with_video(
 
     let reel = app_state
         .reels_service
-        .delete_reel_with_video(reel_id.into_inner())
+        .delete_reel_with_video(reel_id.into_inner(), &user)
         .await?;
 
     Ok(HttpResponse::Ok().json(reel))
 }
 
+#[utoipa::path(
+    get,
+    path = "/feed",
+    params(
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<u32>, Query, description = "Items per page (default: 10, at most 50)")
+    ),
+    responses(
+        (status = 200, description = "Page of reels, each with its video, newest first", body = ReelFeed),
+        (status = 400, description = "Invalid cursor"),
+        (status = 401, description = "Invalid bearer token"),
+        (status = 500, description = "Internal server error")
+    ),
+    description = r#"
+Reels paired with their videos in one list. Reels whose video no longer exists are left out.
+Supersedes `/reel-videos`, which returns reels and videos as separate arrays.
+With a bearer token, every reel also says whether the caller liked and saved it.
+    "#,
+    security(
+        (),
+        ("bearer_auth" = [])
+    ),
+    tag = "Reels"
+)]
+#[get("/feed")]
+async fn get_feed(
+    OptionalUser(viewer): OptionalUser,
+    web::Query(params): web::Query<HashMap<String, String>>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /feed", &app_state.connections);
+
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(10);
+
+    let mut feed = app_state
+        .reels_service
+        .get_feed(params.get("cursor").cloned(), limit)
+        .await?;
+    app_state
+        .engagement_service
+        .mark_reels(viewer.as_ref(), feed.items.iter_mut().map(|item| &mut item.reel).collect())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(feed))
+}
+
 #[utoipa::path(
     get,
     path = "/user/reels",
     params(
-        ("page" = Option<u32>, Query, description = "Page number (default: 1)"),
-        ("limit" = Option<u32>, Query, description = "Items per page (default: 10)")
+        ("page" = Option<u32>, Query, description = "Page number (default: 1), ignored when `cursor` is given"),
+        ("limit" = Option<u32>, Query, description = "Items per page (default: 10, at most 50)"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page; pass it empty for the first page. Switches the response to a `ReelPage`")
     ),
     responses(
-        (status = 200, description = "List of reels for the user", body = [Reel]),
-        (status = 400, description = "Missing user ID in header"),
+        (status = 200, description = "The user's reels, newest first; a `ReelPage` when `cursor` is given", body = [Reel]),
+        (status = 400, description = "Invalid cursor"),
+        (status = 401, description = "Missing or invalid bearer token"),
         (status = 500, description = "Internal server error")
     ),
+    security(
+        ("bearer_auth" = [])
+    ),
     tag = "Reels"
 )]
 #[get("/user/reels")]
 async fn get_reels_by_user_id(
-    req: HttpRequest,
+    user: AuthenticatedUser,
     web::Query(params): web::Query<HashMap<String, String>>,
     app_state: web::Data<AppState<'_>>,
 ) -> Result<impl Responder, AppError> {
     log_request("Get: /reel/user", &app_state.connections);
 
-    let user_uuid = req
-        .headers()
-        .get("x-uuid")
-        .and_then(|h| h.to_str().ok())
-        .ok_or_else(|| AppError::BadRequest("Missing X-Uuid header".into()))?;
-
-    let page = params
-        .get("page")
-        .and_then(|s| s.parse::<u32>().ok())
-        .unwrap_or(1);
     let limit = params
         .get("limit")
         .and_then(|s| s.parse::<u32>().ok())
         .unwrap_or(10);
 
-    let user_id = user_uuid.parse::<Uuid>()
-        .map_err(|_| AppError::BadRequest("Invalid UUID format in X-Uuid header".into()))?;
+    if let Some(cursor) = params.get("cursor") {
+        let mut reels = app_state
+            .reels_service
+            .get_reels_by_user_id_after_cursor(user.id, (!cursor.is_empty()).then(|| cursor.clone()), limit)
+            .await?;
+        app_state
+            .engagement_service
+            .mark_reels(Some(&user), reels.reels.iter_mut().collect())
+            .await?;
+        return Ok(HttpResponse::Ok().json(reels));
+    }
 
-    let reels = app_state
+    let page = params
+        .get("page")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(1);
+    let mut reels = app_state
         .reels_service
-        .get_reels_by_user_id(user_id, page, limit)
+        .get_reels_by_user_id(user.id, page, limit)
         .await?;
-    
+    app_state
+        .engagement_service
+        .mark_reels(Some(&user), reels.iter_mut().collect())
+        .await?;
+
     Ok(HttpResponse::Ok().json(reels))
 }
\ No newline at end of file
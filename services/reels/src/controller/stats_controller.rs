@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use chrono::NaiveDate;
+
+use crate::{
+    auth::AuthenticatedUser, error::{error::AppError, problem::ErrorCode}, model::CreatorStats, service::stats_service::StatsRepository, AppState
+};
+
+use super::log_request;
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_creator_stats);
+}
+
+fn parse_date(params: &HashMap<String, String>, key: &str) -> Result<Option<NaiveDate>, AppError> {
+    params
+        .get(key)
+        .map(|value| value.parse::<NaiveDate>())
+        .transpose()
+        .map_err(|_| AppError::BadRequest(ErrorCode::InvalidQuery, format!("{} must be a date like 2026-10-18", key)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/user/reels/stats",
+    params(
+        ("from" = Option<String>, Query, description = "First UTC day, included (default: 29 days before to)"),
+        ("to" = Option<String>, Query, description = "Last UTC day, included (default: today)")
+    ),
+    responses(
+        (status = 200, description = "Statistics of the user's reels over the range", body = CreatorStats),
+        (status = 400, description = "Invalid date, or a range longer than 366 days"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 500, description = "Internal server error")
+    ),
+    description = r#"
+Views, unique viewers, likes, saves, comments and the average share of the video watched, for every reel
+of the caller and all of them together, with one entry per day. Read from rollups rebuilt in the
+background, so the latest activity shows up after `refreshed_at` moves on.
+    "#,
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Reels"
+)]
+#[get("/user/reels/stats")]
+async fn get_creator_stats(
+    user: AuthenticatedUser,
+    web::Query(params): web::Query<HashMap<String, String>>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /user/reels/stats", &app_state.connections);
+
+    let stats = app_state
+        .stats_service
+        .get_creator_stats(&user, parse_date(&params, "from")?, parse_date(&params, "to")?)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(stats))
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use actix_web::{get, web, HttpResponse, Responder};
+
+use crate::{
+    auth::OptionalUser, error::error::AppError, model::{ReelPage, TagCount}, service::{engagement_service::EngagementRepository, tag_service::TagRepository}, AppState
+};
+
+use super::log_request;
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(autocomplete_tags);
+    cfg.service(get_trending_tags);
+    cfg.service(get_reels_by_tag);
+}
+
+#[utoipa::path(
+    get,
+    path = "/tag/autocomplete",
+    params(
+        ("q" = String, Query, description = "Start of the tag, with or without the #"),
+        ("limit" = Option<u32>, Query, description = "Number of tags (default: 10, at most 50)")
+    ),
+    responses(
+        (status = 200, description = "Tags starting with q, most used first", body = [TagCount]),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Tags"
+)]
+#[get("/tag/autocomplete")]
+async fn autocomplete_tags(
+    web::Query(params): web::Query<HashMap<String, String>>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /tag/autocomplete", &app_state.connections);
+
+    let prefix = params.get("q").map(String::as_str).unwrap_or_default();
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(10);
+
+    let tags = app_state
+        .tag_service
+        .autocomplete_tags(prefix, limit)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tag/trending",
+    params(
+        ("hours" = Option<u32>, Query, description = "Length of the window in hours (default: 168, at most 720)"),
+        ("limit" = Option<u32>, Query, description = "Number of tags (default: 10, at most 50)")
+    ),
+    responses(
+        (status = 200, description = "Tags put on the most reels during the window", body = [TagCount]),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Tags"
+)]
+#[get("/tag/trending")]
+async fn get_trending_tags(
+    web::Query(params): web::Query<HashMap<String, String>>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /tag/trending", &app_state.connections);
+
+    let hours = params
+        .get("hours")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(24 * 7);
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(10);
+
+    let tags = app_state
+        .tag_service
+        .get_trending_tags(hours, limit)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+#[utoipa::path(
+    get,
+    path = "/tag/{name}/reels",
+    params(
+        ("name" = String, Path, description = "The tag, with or without the #"),
+        ("cursor" = Option<String>, Query, description = "`next_cursor` of the previous page"),
+        ("limit" = Option<u32>, Query, description = "Items per page (default: 10, at most 50)")
+    ),
+    responses(
+        (status = 200, description = "Page of reels with the tag, newest first", body = ReelPage),
+        (status = 400, description = "Invalid cursor"),
+        (status = 401, description = "Invalid bearer token"),
+        (status = 500, description = "Internal server error")
+    ),
+    security(
+        (),
+        ("bearer_auth" = [])
+    ),
+    tag = "Tags"
+)]
+#[get("/tag/{name}/reels")]
+async fn get_reels_by_tag(
+    OptionalUser(viewer): OptionalUser,
+    name: web::Path<String>,
+    web::Query(params): web::Query<HashMap<String, String>>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /tag/{name}/reels", &app_state.connections);
+
+    let limit = params
+        .get("limit")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(10);
+
+    let mut reels = app_state
+        .tag_service
+        .get_reels_by_tag(&name, params.get("cursor").cloned(), limit)
+        .await?;
+    app_state
+        .engagement_service
+        .mark_reels(viewer.as_ref(), reels.reels.iter_mut().collect())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(reels))
+}
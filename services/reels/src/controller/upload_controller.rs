@@ -0,0 +1,322 @@
+use actix_web::{
+    delete, head, http::header, middleware::DefaultHeaders, options, patch, post, web, HttpRequest, HttpResponse,
+};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser,
+    error::{error::AppError, problem::ErrorCode}, model::TusUpload, service::upload_service::UploadRepository, util::stream_to_file::append_to_file, AppState
+};
+
+use super::log_request;
+
+const TUS_VERSION: &str = "1.0.0";
+const TUS_EXTENSIONS: &str = "creation,termination,expiration";
+const OFFSET_CONTENT_TYPE: &str = "application/offset+octet-stream";
+
+pub fn init(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/uploads")
+            .wrap(DefaultHeaders::new().add(("Tus-Resumable", TUS_VERSION)))
+            .service(get_upload_options)
+            .service(post_upload)
+            .service(get_upload_offset)
+            .service(patch_upload)
+            .service(delete_upload),
+    );
+}
+
+/// Rejects requests from clients speaking another version of the protocol.
+fn check_tus_resumable(req: &HttpRequest) -> Result<(), HttpResponse> {
+    match req.headers().get("Tus-Resumable").and_then(|h| h.to_str().ok()) {
+        Some(TUS_VERSION) => Ok(()),
+        _ => Err(HttpResponse::PreconditionFailed()
+            .insert_header(("Tus-Version", TUS_VERSION))
+            .finish()),
+    }
+}
+
+fn parse_length_header(req: &HttpRequest, name: &str) -> Result<i64, AppError> {
+    req.headers()
+        .get(name)
+        .and_then(|h| h.to_str().ok())
+        .ok_or_else(|| AppError::BadRequest(ErrorCode::InvalidUploadHeader, format!("Missing {} header", name)))?
+        .parse::<i64>()
+        .ok()
+        .filter(|value| *value >= 0)
+        .ok_or_else(|| AppError::BadRequest(ErrorCode::InvalidUploadHeader, format!("Invalid {} header", name)))
+}
+
+fn upload_state(upload: &TusUpload) -> HttpResponse {
+    let mut response = HttpResponse::NoContent();
+    response
+        .insert_header(("Upload-Offset", upload.upload_offset.to_string()))
+        .insert_header(("Upload-Expires", upload.expires_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()))
+        .insert_header((header::CACHE_CONTROL, "no-store"));
+    if let Some(video_id) = upload.video_id {
+        response.insert_header(("x-video-id", video_id.to_string()));
+    }
+    response.finish()
+}
+
+#[utoipa::path(
+    options,
+    path = "/uploads",
+    responses(
+        (status = 204, description = "Protocol versions, extensions and the maximum upload size")
+    ),
+    tag = "Upload"
+)]
+#[options("")]
+async fn get_upload_options(app_state: web::Data<AppState<'_>>) -> HttpResponse {
+    log_request("Options: /uploads", &app_state.connections);
+
+    HttpResponse::NoContent()
+        .insert_header(("Tus-Version", TUS_VERSION))
+        .insert_header(("Tus-Extension", TUS_EXTENSIONS))
+        .insert_header((
+            "Tus-Max-Size",
+            app_state.upload_service.upload.max_file_size_bytes().to_string(),
+        ))
+        .finish()
+}
+
+#[utoipa::path(
+    post,
+    path = "/uploads",
+    params(
+        ("Upload-Length" = i64, Header, description = "Size of the whole file in bytes"),
+        ("Upload-Metadata" = String, Header, description = "Base64 encoded `filename`, optional `filetype` and `video` (PostVideo JSON)")
+    ),
+    responses(
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 201, description = "Upload created, its URL is in the Location header"),
+        (status = 400, description = "Missing or invalid headers or metadata"),
+        (status = 412, description = "Unsupported Tus-Resumable version"),
+        (status = 413, description = "File exceeds the maximum upload size")
+    ),
+    description = r#"
+Create a resumable upload (tus 1.0). The file is sent with PATCH requests to the returned URL and
+turned into a video, as with POST /video, once the last byte arrives.
+
+        curl -i -X 'POST' \
+        'http://127.0.0.1:8000/uploads' \
+        -H 'Tus-Resumable: 1.0.0' \
+        -H 'Authorization: Bearer <access token>' \
+        -H 'Upload-Length: 1048576' \
+        -H "Upload-Metadata: filename $(echo -n epico.mp4 | base64),video $(echo -n '{"title":"string","description":"string"}' | base64 -w0)"
+    "#,
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Upload"
+)]
+#[post("")]
+async fn post_upload(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<HttpResponse, AppError> {
+    log_request("Post: /uploads", &app_state.connections);
+
+    if let Err(response) = check_tus_resumable(&req) {
+        return Ok(response);
+    }
+
+    let posting_user_id = user.id;
+
+    let upload_length = parse_length_header(&req, "Upload-Length")?;
+    let metadata = req
+        .headers()
+        .get("Upload-Metadata")
+        .and_then(|h| h.to_str().ok())
+        .ok_or_else(|| AppError::BadRequest(ErrorCode::InvalidUploadHeader, "Missing Upload-Metadata header".into()))?
+        .to_string();
+
+    let upload = app_state
+        .upload_service
+        .post_upload(posting_user_id, upload_length, metadata)
+        .await?;
+
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/uploads/{}", upload.id)))
+        .insert_header(("Upload-Expires", upload.expires_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()))
+        .finish())
+}
+
+#[utoipa::path(
+    head,
+    path = "/uploads/{id}",
+    params(
+        ("id" = Uuid, Path, description = "ID of the upload")
+    ),
+    responses(
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 204, description = "Received bytes in Upload-Offset, and the created video in x-video-id once complete"),
+        (status = 403, description = "Upload belongs to another user"),
+        (status = 404, description = "Upload not found or expired")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Upload"
+)]
+#[head("/{id}")]
+async fn get_upload_offset(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    upload_id: web::Path<Uuid>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<HttpResponse, AppError> {
+    log_request("Head: /uploads/{id}", &app_state.connections);
+
+    if let Err(response) = check_tus_resumable(&req) {
+        return Ok(response);
+    }
+
+    let upload = app_state
+        .upload_service
+        .get_upload(upload_id.into_inner(), &user)
+        .await?;
+
+    let mut response = upload_state(&upload);
+    response.headers_mut().insert(
+        header::HeaderName::from_static("upload-length"),
+        header::HeaderValue::from(upload.upload_length),
+    );
+    if let Ok(metadata) = header::HeaderValue::from_str(&upload.metadata) {
+        response
+            .headers_mut()
+            .insert(header::HeaderName::from_static("upload-metadata"), metadata);
+    }
+    Ok(response)
+}
+
+#[utoipa::path(
+    patch,
+    path = "/uploads/{id}",
+    params(
+        ("id" = Uuid, Path, description = "ID of the upload"),
+        ("Upload-Offset" = i64, Header, description = "Offset the body starts at, must match the received bytes")
+    ),
+    request_body(
+        content = String,
+        content_type = "application/offset+octet-stream"
+    ),
+    responses(
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 204, description = "Bytes appended, new offset in Upload-Offset and the created video in x-video-id once complete"),
+        (status = 400, description = "Completed file is not a supported video"),
+        (status = 403, description = "Upload belongs to another user"),
+        (status = 404, description = "Upload not found or expired"),
+        (status = 409, description = "Upload-Offset does not match the received bytes, or another PATCH is in progress"),
+        (status = 413, description = "Body goes past Upload-Length"),
+        (status = 415, description = "Body is not application/offset+octet-stream")
+    ),
+    description = r#"
+Append to an upload. Bytes received before a dropped connection are kept, so a client resumes
+from the offset returned by HEAD. An empty PATCH at the full length retries creating the video.
+    "#,
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Upload"
+)]
+#[patch("/{id}")]
+async fn patch_upload(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    upload_id: web::Path<Uuid>,
+    mut payload: web::Payload,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<HttpResponse, AppError> {
+    log_request("Patch: /uploads/{id}", &app_state.connections);
+
+    if let Err(response) = check_tus_resumable(&req) {
+        return Ok(response);
+    }
+
+    let content_type = req.headers().get(header::CONTENT_TYPE).and_then(|h| h.to_str().ok());
+    if content_type != Some(OFFSET_CONTENT_TYPE) {
+        return Err(AppError::UnsupportedMediaType(ErrorCode::UnsupportedUploadMediaType, format!(
+            "Content-Type must be {}",
+            OFFSET_CONTENT_TYPE
+        )));
+    }
+    let offset = parse_length_header(&req, "Upload-Offset")?;
+
+    let upload_service = &app_state.upload_service;
+    let upload = upload_service
+        .get_upload(upload_id.into_inner(), &user)
+        .await?;
+    let upload = upload_service.lock_upload(upload.id).await?;
+    let upload_id = upload.id;
+
+    let response = async {
+        if upload.upload_offset != offset {
+            return Err(AppError::Conflict(ErrorCode::UploadOffsetMismatch, format!(
+                "Upload-Offset {} does not match the {} bytes received",
+                offset, upload.upload_offset
+            )));
+        }
+        if upload.video_id.is_some() {
+            return Ok(upload_state(&upload));
+        }
+
+        let remaining = (upload.upload_length - upload.upload_offset) as u64;
+        let (written, result) = append_to_file(
+            &mut payload,
+            &upload_service.file_path(upload.id),
+            upload.upload_offset as u64,
+            remaining,
+        )
+        .await;
+
+        // Keep whatever arrived, even when the body broke off.
+        let upload = upload_service.commit_upload(upload, written).await?;
+        result?;
+        Ok(upload_state(&upload))
+    }
+    .await;
+
+    upload_service.unlock_upload(upload_id).await;
+    response
+}
+
+#[utoipa::path(
+    delete,
+    path = "/uploads/{id}",
+    params(
+        ("id" = Uuid, Path, description = "ID of the upload to terminate")
+    ),
+    responses(
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 204, description = "Upload terminated and received bytes removed"),
+        (status = 403, description = "Upload belongs to another user"),
+        (status = 404, description = "Upload not found or expired")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Upload"
+)]
+#[delete("/{id}")]
+async fn delete_upload(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    upload_id: web::Path<Uuid>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<HttpResponse, AppError> {
+    log_request("Delete: /uploads/{id}", &app_state.connections);
+
+    if let Err(response) = check_tus_resumable(&req) {
+        return Ok(response);
+    }
+
+    app_state
+        .upload_service
+        .delete_upload(upload_id.into_inner(), &user)
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -1,11 +1,14 @@
+use std::path::Path;
+
 use actix_multipart::{Field, Multipart};
-use actix_web::{delete, get, post, put, web::{self, BytesMut}, HttpResponse, Responder, HttpRequest};
+use actix_web::{delete, get, http::header, post, put, web, HttpResponse, Responder};
 use serde_json::from_slice;
 use uuid::Uuid;
 use futures_util::StreamExt as _;
 
 use crate::{
-    error::error::AppError, model::{PostVideo, Video, VideoForm}, service::video_service::VideoRepository, util::read_bytes::read_bytes, AppState
+    auth::AuthenticatedUser,
+    error::{error::AppError, problem::ErrorCode}, model::{CoverForm, PostVideo, PosterFrame, RecipeSummary, Video, VideoForm}, service::video_service::VideoRepository, util::{read_bytes::read_bytes, stream_to_file::{stream_to_file, TempUpload}}, AppState
 };
 
 use super::log_request;
@@ -13,8 +16,12 @@ use super::log_request;
 pub fn init(cfg: &mut web::ServiceConfig) {
     cfg.service(get_video_by_id);
     cfg.service(get_video_by_reel_id);
+    cfg.service(get_recipes_by_video_id);
     cfg.service(post_video);
     cfg.service(put_video);
+    cfg.service(put_video_file);
+    cfg.service(put_poster_frame);
+    cfg.service(put_cover);
     cfg.service(delete_video);
 }
 
@@ -81,7 +88,8 @@ async fn get_video_by_reel_id(
         content_type = "multipart/form-data"
     ),
     responses(
-        (status = 200, description = "Video uploaded successfully"),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 201, description = "Video uploaded successfully, its URL is in the Location header", body = Video),
         (status = 400, description = "Invalid input")
     ),    description = r#"
         KNOWN utoipa ERROR, CURL WON'T GENERATE PROPERLY
@@ -91,67 +99,92 @@ async fn get_video_by_reel_id(
         'http://127.0.0.1:8000/video' \
         -H 'accept: */*' \
         -H 'Content-Type: multipart/form-data' \
-        -H 'x-uuid: 3fa85f64-5717-4562-b3fc-2c963f66afa6' \
+        -H 'Authorization: Bearer <access token>' \
         -F 'file=@epico.mp4;type=video/mp4' \
-        -F 'video={"description":"string","title":"string","video_length_seconds":1073741824};type=application/json'
+        -F 'video={"description":"string","title":"string"};type=application/json'
 
     "#,
+    security(
+        ("bearer_auth" = [])
+    ),
     tag = "Video"
 )]
 #[post("/video")]
 async fn post_video(
-    req: HttpRequest,
+    user: AuthenticatedUser,
     mut payload: Multipart,
     app_state: web::Data<AppState<'_>>,
 ) -> Result<impl Responder, AppError> {
     log_request("Post: /video", &app_state.connections);
 
-    let posting_user_id = req
-        .headers()
-        .get("x-uuid")
-        .and_then(|h| h.to_str().ok())
-        .ok_or_else(|| AppError::BadRequest("Missing x-uuid header".into()))?
-        .parse::<Uuid>()
-        .map_err(|_| AppError::BadRequest("Invalid UUID format in x-uuid header".into()))?;
+    let posting_user_id = user.id;
 
     let mut video_metadata: Option<PostVideo> = None;
-    let mut video_data: Option<BytesMut> = None;
-    let mut file_name: Option<String> = None;
+    let mut video_data: Option<TempUpload> = None;
+    let mut cover_data: Option<TempUpload> = None;
 
     while let Some(item) = payload.next().await {
         let mut field: Field = item
-            .map_err(|_| AppError::BadRequest("Invalid multipart file".into()))?;
+            .map_err(|_| AppError::BadRequest(ErrorCode::InvalidMultipart, "Invalid multipart file".into()))?;
 
         let disposition = field.content_disposition();
         let name = disposition.and_then(|d| d.get_name()).unwrap_or("");
 
         match name {
             "file" => {
-                file_name = disposition.and_then(|d| d.get_filename().map(str::to_string));
-                video_data = Some(read_bytes(&mut field).await?);
+                let file_name = disposition
+                    .and_then(|d| d.get_filename().map(str::to_string))
+                    .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing file name".into()))?;
+                let upload = &app_state.video_service.upload;
+                video_data = Some(
+                    stream_to_file(
+                        &mut field,
+                        Path::new(&upload.directory),
+                        file_name,
+                        upload.max_file_size_bytes(),
+                    )
+                    .await?,
+                );
+            }
+            "cover" => {
+                let file_name = disposition
+                    .and_then(|d| d.get_filename().map(str::to_string))
+                    .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing cover file name".into()))?;
+                let upload = &app_state.video_service.upload;
+                cover_data = Some(
+                    stream_to_file(
+                        &mut field,
+                        Path::new(&upload.directory),
+                        file_name,
+                        upload.max_file_size_bytes(),
+                    )
+                    .await?,
+                );
             }
             "video" => {
                 let json_bytes = read_bytes(&mut field).await?;
                 video_metadata = Some(
                     from_slice(&json_bytes)
-                        .map_err(|_| AppError::BadRequest("Invalid JSON in 'video'".into()))?,
+                        .map_err(|_| AppError::BadRequest(ErrorCode::InvalidMultipart, "Invalid JSON in 'video'".into()))?,
                 );
             }
-            _ => return Err(AppError::BadRequest("Invalid multipart file".into()))
+            _ => return Err(AppError::BadRequest(ErrorCode::InvalidMultipart, "Invalid multipart file".into()))
         }
     }
 
     let video_metadata = video_metadata
-        .ok_or_else(|| AppError::BadRequest("Missing video metadata".into()))?;
+        .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing video metadata".into()))?;
     let video_data = video_data
-        .ok_or_else(|| AppError::BadRequest("Missing file field".into()))?;
-    let file_name = file_name
-        .ok_or_else(|| AppError::BadRequest("Missing file name".into()))?;    app_state
+        .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing file field".into()))?;
+
+    let video = app_state
         .video_service
-        .post_video(video_metadata, posting_user_id, video_data, file_name)
+        .post_video(video_metadata, posting_user_id, video_data, cover_data)
         .await?;
-    
-    Ok(HttpResponse::Ok().finish())
+
+    Ok(HttpResponse::Created()
+        .insert_header((header::LOCATION, format!("/video/{}", video.id)))
+        .json(video))
 }
 
 #[utoipa::path(
@@ -165,32 +198,246 @@ async fn post_video(
         content_type = "application/json"
     ),
     responses(
+        (status = 401, description = "Missing or invalid bearer token"),
         (status = 200, description = "Video updated successfully", body = Video),
         (status = 404, description = "Video not found"),
+        (status = 403, description = "Video is owned by another user and caller is not a moderator"),
         (status = 400, description = "Invalid input")
     ),
+    security(
+        ("bearer_auth" = [])
+    ),
     tag = "Video"
 )]
 #[put("/video/{id}")]
 async fn put_video(
-    req: HttpRequest,
+    user: AuthenticatedUser,
     video_id: web::Path<Uuid>,
     video: web::Json<PostVideo>,
     app_state: web::Data<AppState<'_>>,
 ) -> Result<impl Responder, AppError> {
-    log_request("Put: /reel", &app_state.connections);
+    log_request("Put: /video/{id}", &app_state.connections);
+
+    let video = app_state
+        .video_service
+        .put_video(video_id.into_inner(), video.into_inner(), &user)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(video))
+}
+
+#[utoipa::path(
+    put,
+    path = "/video/{id}/file",
+    params(
+        ("id" = Uuid, Path, description = "ID of the video whose file is replaced")
+    ),
+    request_body(
+        content = VideoForm,
+        content_type = "multipart/form-data"
+    ),
+    responses(
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 200, description = "Video file replaced successfully", body = Video),
+        (status = 400, description = "Invalid input"),
+        (status = 403, description = "Video is owned by another user and caller is not a moderator"),
+        (status = 404, description = "Video not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    description = r#"
+Replace the file of an existing video. The `video` metadata field is optional;
+when present it also updates the title, description and length.
+The poster is regenerated from the new file unless a `cover` image is sent.
+The previous file is removed only after the database row is updated.
+    "#,
+    tag = "Video"
+)]
+#[put("/video/{id}/file")]
+async fn put_video_file(
+    user: AuthenticatedUser,
+    video_id: web::Path<Uuid>,
+    mut payload: Multipart,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Put: /video/{id}/file", &app_state.connections);
 
-    let posting_user_id = req
-        .headers()
-        .get("x-uuid")
-        .and_then(|h| h.to_str().ok())
-        .ok_or_else(|| AppError::BadRequest("Missing x-uuid header".into()))?
-        .parse::<Uuid>()
-        .map_err(|_| AppError::BadRequest("Invalid UUID format in x-uuid header".into()))?;
+    let mut video_metadata: Option<PostVideo> = None;
+    let mut video_data: Option<TempUpload> = None;
+    let mut cover_data: Option<TempUpload> = None;
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item
+            .map_err(|_| AppError::BadRequest(ErrorCode::InvalidMultipart, "Invalid multipart file".into()))?;
+
+        let disposition = field.content_disposition();
+        let name = disposition.and_then(|d| d.get_name()).unwrap_or("");
 
-    let video =  app_state
+        match name {
+            "file" => {
+                let file_name = disposition
+                    .and_then(|d| d.get_filename().map(str::to_string))
+                    .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing file name".into()))?;
+                let upload = &app_state.video_service.upload;
+                video_data = Some(
+                    stream_to_file(
+                        &mut field,
+                        Path::new(&upload.directory),
+                        file_name,
+                        upload.max_file_size_bytes(),
+                    )
+                    .await?,
+                );
+            }
+            "cover" => {
+                let file_name = disposition
+                    .and_then(|d| d.get_filename().map(str::to_string))
+                    .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing cover file name".into()))?;
+                let upload = &app_state.video_service.upload;
+                cover_data = Some(
+                    stream_to_file(
+                        &mut field,
+                        Path::new(&upload.directory),
+                        file_name,
+                        upload.max_file_size_bytes(),
+                    )
+                    .await?,
+                );
+            }
+            "video" => {
+                let json_bytes = read_bytes(&mut field).await?;
+                video_metadata = Some(
+                    from_slice(&json_bytes)
+                        .map_err(|_| AppError::BadRequest(ErrorCode::InvalidMultipart, "Invalid JSON in 'video'".into()))?,
+                );
+            }
+            _ => return Err(AppError::BadRequest(ErrorCode::InvalidMultipart, "Invalid multipart file".into()))
+        }
+    }
+
+    let video_data = video_data
+        .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing file field".into()))?;
+
+    let video = app_state
         .video_service
-        .put_video(video_id.into_inner(), video.into_inner(), posting_user_id)
+        .put_video_file(video_id.into_inner(), video_metadata, &user, video_data, cover_data)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(video))
+}
+
+#[utoipa::path(
+    put,
+    path = "/video/{id}/poster",
+    params(
+        ("id" = Uuid, Path, description = "ID of the video whose poster is regenerated")
+    ),
+    request_body(
+        content = PosterFrame,
+        content_type = "application/json"
+    ),
+    responses(
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 200, description = "Poster regenerated successfully", body = Video),
+        (status = 400, description = "Invalid offset"),
+        (status = 403, description = "Video is owned by another user and caller is not a moderator"),
+        (status = 404, description = "Video not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    description = r#"
+Regenerate the poster and thumbnail from the frame at `offset_seconds`.
+    "#,
+    tag = "Video"
+)]
+#[put("/video/{id}/poster")]
+async fn put_poster_frame(
+    user: AuthenticatedUser,
+    video_id: web::Path<Uuid>,
+    frame: web::Json<PosterFrame>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Put: /video/{id}/poster", &app_state.connections);
+
+    let video = app_state
+        .video_service
+        .put_poster_frame(video_id.into_inner(), frame.into_inner(), &user)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(video))
+}
+
+#[utoipa::path(
+    put,
+    path = "/video/{id}/cover",
+    params(
+        ("id" = Uuid, Path, description = "ID of the video whose cover is uploaded")
+    ),
+    request_body(
+        content = CoverForm,
+        content_type = "multipart/form-data"
+    ),
+    responses(
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 200, description = "Cover uploaded successfully", body = Video),
+        (status = 400, description = "File is not a readable image"),
+        (status = 403, description = "Video is owned by another user and caller is not a moderator"),
+        (status = 404, description = "Video not found")
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    description = r#"
+Upload a custom cover image. It replaces the poster and a thumbnail is generated from it.
+    "#,
+    tag = "Video"
+)]
+#[put("/video/{id}/cover")]
+async fn put_cover(
+    user: AuthenticatedUser,
+    video_id: web::Path<Uuid>,
+    mut payload: Multipart,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Put: /video/{id}/cover", &app_state.connections);
+
+    let mut cover_data: Option<TempUpload> = None;
+
+    while let Some(item) = payload.next().await {
+        let mut field: Field = item
+            .map_err(|_| AppError::BadRequest(ErrorCode::InvalidMultipart, "Invalid multipart file".into()))?;
+
+        let disposition = field.content_disposition();
+        let name = disposition.and_then(|d| d.get_name()).unwrap_or("");
+
+        match name {
+            "file" => {
+                let file_name = disposition
+                    .and_then(|d| d.get_filename().map(str::to_string))
+                    .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing file name".into()))?;
+                let upload = &app_state.video_service.upload;
+                cover_data = Some(
+                    stream_to_file(
+                        &mut field,
+                        Path::new(&upload.directory),
+                        file_name,
+                        upload.max_file_size_bytes(),
+                    )
+                    .await?,
+                );
+            }
+            _ => return Err(AppError::BadRequest(ErrorCode::InvalidMultipart, "Invalid multipart file".into()))
+        }
+    }
+
+    let cover_data = cover_data
+        .ok_or_else(|| AppError::BadRequest(ErrorCode::MissingField, "Missing file field".into()))?;
+
+    let video = app_state
+        .video_service
+        .put_cover(video_id.into_inner(), cover_data, &user)
         .await?;
 
     Ok(HttpResponse::Ok().json(video))
@@ -203,13 +450,19 @@ async fn put_video(
         ("id" = Uuid, Path, description = "ID of the video to delete")
     ),
     responses(
-        (status = 200, description = "Video deleted successfully", body = String),
+        (status = 401, description = "Missing or invalid bearer token"),
+        (status = 403, description = "Owned by another user and caller is not a moderator"),
+        (status = 200, description = "The deleted video", body = Video),
         (status = 404, description = "Video not found")
     ),
+    security(
+        ("bearer_auth" = [])
+    ),
     tag = "Video"
 )]
 #[delete("/video/{id}")]
 async fn delete_video(
+    user: AuthenticatedUser,
     video_id: web::Path<Uuid>,
     app_state: web::Data<AppState<'_>>,
 ) -> Result<impl Responder, AppError> {
@@ -217,8 +470,35 @@ async fn delete_video(
 
     let video = app_state
         .video_service
-        .delete_video(video_id.into_inner())
+        .delete_video(video_id.into_inner(), &user)
         .await?;
 
     Ok(HttpResponse::Ok().json(video))
 }
+
+#[utoipa::path(
+    get,
+    path = "/video/{id}/recipes",
+    params(
+        ("id" = Uuid, Path, description = "ID of the video")
+    ),
+    responses(
+        (status = 200, description = "Recipes the video is shown on", body = [RecipeSummary]),
+        (status = 404, description = "Video not found")
+    ),
+    tag = "Video"
+)]
+#[get("/video/{id}/recipes")]
+async fn get_recipes_by_video_id(
+    video_id: web::Path<Uuid>,
+    app_state: web::Data<AppState<'_>>,
+) -> Result<impl Responder, AppError> {
+    log_request("Get: /video/{id}/recipes", &app_state.connections);
+
+    let recipes = app_state
+        .video_service
+        .get_recipes_by_video_id(video_id.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(recipes))
+}
@@ -0,0 +1,26 @@
+use crate::model::AuditEntry;
+
+use super::database_context::Table;
+
+impl<'c> Table<'c, AuditEntry> {
+    pub async fn post_entry(&self, entry: &AuditEntry) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+                INSERT INTO audit_log
+                    (id, actor_id, actor_roles, action, resource_id, resource_owner_id, outcome, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(entry.id)
+        .bind(entry.actor_id)
+        .bind(&entry.actor_roles)
+        .bind(&entry.action)
+        .bind(entry.resource_id)
+        .bind(entry.resource_owner_id)
+        .bind(entry.outcome.as_str())
+        .bind(entry.created_at)
+        .execute(&*self.pool)
+        .await
+        .map(|_| ())
+    }
+}
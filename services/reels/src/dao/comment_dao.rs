@@ -0,0 +1,144 @@
+use uuid::Uuid;
+
+use crate::model::{Comment, CommentCursor, CommentSort};
+
+use super::database_context::Table;
+
+impl<'c> Table<'c, Comment> {
+    pub async fn get_comment_by_id(&self, comment_id: Uuid) -> Result<Comment, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM reel_comments WHERE id = $1")
+            .bind(comment_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    /// Comments on the reel that are not replies. The top comments need a cursor with a reply count.
+    pub async fn get_comments_by_reel_id_after_cursor(
+        &self,
+        reel_id: Uuid,
+        sort: CommentSort,
+        cursor: Option<&CommentCursor>,
+        limit: i64,
+    ) -> Result<Vec<Comment>, sqlx::Error> {
+        let query = match sort {
+            CommentSort::Newest => {
+                r#"
+                    SELECT *
+                    FROM reel_comments
+                    WHERE reel_id = $1 AND parent_id IS NULL
+                        AND ($2::timestamptz IS NULL OR (created_at, id) < ($2, $3))
+                    ORDER BY created_at DESC, id DESC
+                    LIMIT $5
+                "#
+            }
+            CommentSort::Top => {
+                r#"
+                    SELECT *
+                    FROM reel_comments
+                    WHERE reel_id = $1 AND parent_id IS NULL
+                        AND ($2::timestamptz IS NULL OR (reply_count, created_at, id) < ($4, $2, $3))
+                    ORDER BY reply_count DESC, created_at DESC, id DESC
+                    LIMIT $5
+                "#
+            }
+        };
+
+        sqlx::query_as(query)
+            .bind(reel_id)
+            .bind(cursor.map(|c| c.created_at))
+            .bind(cursor.map(|c| c.id))
+            .bind(cursor.and_then(|c| c.reply_count))
+            .bind(limit)
+            .fetch_all(&*self.pool)
+            .await
+    }
+
+    /// Replies to the comment, oldest first so a thread reads top to bottom.
+    pub async fn get_replies_after_cursor(
+        &self,
+        parent_id: Uuid,
+        cursor: Option<&CommentCursor>,
+        limit: i64,
+    ) -> Result<Vec<Comment>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT *
+                FROM reel_comments
+                WHERE parent_id = $1
+                    AND ($2::timestamptz IS NULL OR (created_at, id) > ($2, $3))
+                ORDER BY created_at, id
+                LIMIT $4
+            "#,
+        )
+        .bind(parent_id)
+        .bind(cursor.map(|c| c.created_at))
+        .bind(cursor.map(|c| c.id))
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Inserts the comment and counts it on its parent, if it is a reply.
+    pub async fn post_comment(&self, comment: &Comment) -> Result<Comment, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                WITH parent AS (
+                    UPDATE reel_comments
+                    SET reply_count = reply_count + 1
+                    WHERE id = $3
+                )
+                INSERT INTO reel_comments (id, reel_id, parent_id, author_id, body, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                RETURNING *
+            "#,
+        )
+        .bind(comment.id)
+        .bind(comment.reel_id)
+        .bind(comment.parent_id)
+        .bind(comment.author_id)
+        .bind(&comment.body)
+        .bind(comment.created_at)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    /// Replaces the body of a comment that is not deleted.
+    pub async fn put_comment(&self, comment_id: Uuid, body: &str) -> Result<Comment, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                UPDATE reel_comments
+                SET body = $2,
+                    updated_at = now()
+                WHERE id = $1 AND deleted_at IS NULL
+                RETURNING *
+            "#,
+        )
+        .bind(comment_id)
+        .bind(body)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    /// Clears the body and marks the comment deleted, so its replies stay listed under it.
+    /// Deleting it again changes nothing.
+    pub async fn delete_comment(&self, comment_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+                WITH removed AS (
+                    UPDATE reel_comments
+                    SET body = '',
+                        deleted_at = now()
+                    WHERE id = $1 AND deleted_at IS NULL
+                    RETURNING parent_id
+                )
+                UPDATE reel_comments
+                SET reply_count = reply_count - 1
+                WHERE id IN (SELECT parent_id FROM removed)
+            "#,
+        )
+        .bind(comment_id)
+        .execute(&*self.pool)
+        .await
+        .map(|_| ())
+    }
+}
@@ -1,13 +1,22 @@
 use sqlx::postgres::PgRow;
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgPool, Postgres, Transaction};
 use std::marker::PhantomData;
 use std::sync::Arc;
 
-use crate::model::{Reel, Video};
+use crate::model::{AuditEntry, Comment, MediaJob, RecipeVideo, Reel, ReelDailyStats, ReelEvent, ReelReaction, Tag, TusUpload, Video};
 
 pub struct Database<'c> {
     pub reels: Arc<Table<'c, Reel>>,
     pub videos: Arc<Table<'c, Video>>,
+    pub jobs: Arc<Table<'c, MediaJob>>,
+    pub uploads: Arc<Table<'c, TusUpload>>,
+    pub audit: Arc<Table<'c, AuditEntry>>,
+    pub recipe_videos: Arc<Table<'c, RecipeVideo>>,
+    pub tags: Arc<Table<'c, Tag>>,
+    pub reactions: Arc<Table<'c, ReelReaction>>,
+    pub comments: Arc<Table<'c, Comment>>,
+    pub events: Arc<Table<'c, ReelEvent>>,
+    pub stats: Arc<Table<'c, ReelDailyStats>>,
 }
 
 impl<'a> Database<'a> {
@@ -18,8 +27,22 @@ impl<'a> Database<'a> {
         Database {
             reels: Arc::from(Table::new(pool.clone())),
             videos: Arc::from(Table::new(pool.clone())),
+            jobs: Arc::from(Table::new(pool.clone())),
+            uploads: Arc::from(Table::new(pool.clone())),
+            audit: Arc::from(Table::new(pool.clone())),
+            recipe_videos: Arc::from(Table::new(pool.clone())),
+            tags: Arc::from(Table::new(pool.clone())),
+            reactions: Arc::from(Table::new(pool.clone())),
+            comments: Arc::from(Table::new(pool.clone())),
+            events: Arc::from(Table::new(pool.clone())),
+            stats: Arc::from(Table::new(pool.clone())),
         }
     }
+
+    /// Starts a transaction for writes spanning several tables, passed to the `*_with` DAO methods.
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        self.reels.pool.begin().await
+    }
 }
 
 pub struct Table<'c, T>
@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgExecutor, Row};
+use uuid::Uuid;
+
+use crate::model::{ReactionKind, Reel, ReelCursor, ReelEngagement, ReelReaction};
+
+use super::database_context::Table;
+
+impl<'c> Table<'c, ReelReaction> {
+    /// Records the reaction of the user and bumps the counter of the reel. Reacting again, or to a reel
+    /// that does not exist, changes nothing.
+    pub async fn post_reaction_with<'e, E: PgExecutor<'e>>(
+        &self,
+        executor: E,
+        kind: ReactionKind,
+        user_id: Uuid,
+        reel_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(&format!(
+            r#"
+                WITH added AS (
+                    INSERT INTO {table} (user_id, reel_id)
+                    SELECT $1, id FROM reels WHERE id = $2
+                    ON CONFLICT (user_id, reel_id) DO NOTHING
+                    RETURNING reel_id
+                )
+                UPDATE reels
+                SET {counter} = {counter} + 1
+                WHERE id IN (SELECT reel_id FROM added)
+            "#,
+            table = kind.table(),
+            counter = kind.counter(),
+        ))
+        .bind(user_id)
+        .bind(reel_id)
+        .execute(executor)
+        .await
+        .map(|_| ())
+    }
+
+    /// Removes the reaction of the user and lowers the counter of the reel, if there was one.
+    pub async fn delete_reaction_with<'e, E: PgExecutor<'e>>(
+        &self,
+        executor: E,
+        kind: ReactionKind,
+        user_id: Uuid,
+        reel_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(&format!(
+            r#"
+                WITH removed AS (
+                    DELETE FROM {table}
+                    WHERE user_id = $1 AND reel_id = $2
+                    RETURNING reel_id
+                )
+                UPDATE reels
+                SET {counter} = {counter} - 1
+                WHERE id IN (SELECT reel_id FROM removed)
+            "#,
+            table = kind.table(),
+            counter = kind.counter(),
+        ))
+        .bind(user_id)
+        .bind(reel_id)
+        .execute(executor)
+        .await
+        .map(|_| ())
+    }
+
+    /// The counters of the reel and the reactions of the user to it, `None` when the reel does not exist.
+    pub async fn get_engagement_with<'e, E: PgExecutor<'e>>(
+        &self,
+        executor: E,
+        user_id: Uuid,
+        reel_id: Uuid,
+    ) -> Result<Option<ReelEngagement>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT r.id AS reel_id, r.like_count, r.save_count,
+                    EXISTS (SELECT 1 FROM reel_likes l WHERE l.user_id = $1 AND l.reel_id = r.id) AS liked_by_me,
+                    EXISTS (SELECT 1 FROM reel_saves s WHERE s.user_id = $1 AND s.reel_id = r.id) AS saved_by_me
+                FROM reels r
+                WHERE r.id = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(reel_id)
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// Which of `reel_ids` the user reacted to.
+    pub async fn get_reacted_reel_ids(
+        &self,
+        kind: ReactionKind,
+        user_id: Uuid,
+        reel_ids: &[Uuid],
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(&format!(
+            "SELECT reel_id FROM {} WHERE user_id = $1 AND reel_id = ANY($2)",
+            kind.table()
+        ))
+        .bind(user_id)
+        .bind(reel_ids)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Reels saved by the user with the time they were saved, most recently saved first. The cursor
+    /// holds the save time rather than the creation time of the reel.
+    pub async fn get_saved_reels_after_cursor(
+        &self,
+        user_id: Uuid,
+        cursor: Option<&ReelCursor>,
+        limit: i64,
+    ) -> Result<Vec<(Reel, DateTime<Utc>)>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+                SELECT r.*, s.created_at AS saved_at
+                FROM reel_saves s
+                JOIN reels r ON r.id = s.reel_id
+                WHERE s.user_id = $1
+                    AND ($2::timestamptz IS NULL OR (s.created_at, s.reel_id) < ($2, $3))
+                ORDER BY s.created_at DESC, s.reel_id DESC
+                LIMIT $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(cursor.map(|c| c.creation_timestamp.and_utc()))
+        .bind(cursor.map(|c| c.id))
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok((Reel::from_row(row)?, row.try_get("saved_at")?)))
+            .collect()
+    }
+}
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::model::{ReelEvent, ReelView};
+
+use super::database_context::Table;
+
+impl<'c> Table<'c, ReelEvent> {
+    /// Length of the video of each reel that exists, in seconds.
+    pub async fn get_video_lengths(&self, reel_ids: &[Uuid]) -> Result<Vec<(Uuid, i32)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT r.id, v.video_length_seconds
+                FROM reels r
+                JOIN videos v ON v.id = r.video_id
+                WHERE r.id = ANY($1)
+            "#,
+        )
+        .bind(reel_ids)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Appends the event. Its `id` and `received_at` are set by the database.
+    pub async fn post_event_with<'e, E: PgExecutor<'e>>(&self, executor: E, event: &ReelEvent) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+                INSERT INTO reel_events (reel_id, user_id, session_id, kind, watch_ms, occurred_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(event.reel_id)
+        .bind(event.user_id)
+        .bind(&event.session_id)
+        .bind(event.kind.as_str())
+        .bind(event.watch_ms)
+        .bind(event.occurred_at)
+        .execute(executor)
+        .await
+        .map(|_| ())
+    }
+
+    /// Serializes the views of one viewer of a reel until the transaction ends, so concurrent
+    /// batches cannot both start a view.
+    pub async fn lock_viewer_with<'e, E: PgExecutor<'e>>(&self, executor: E, reel_id: Uuid, viewer: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1::text || $2, 0))")
+            .bind(reel_id)
+            .bind(viewer)
+            .execute(executor)
+            .await
+            .map(|_| ())
+    }
+
+    /// The latest view of the reel by the viewer, if it was still going at `since`.
+    pub async fn get_open_view_with<'e, E: PgExecutor<'e>>(
+        &self,
+        executor: E,
+        reel_id: Uuid,
+        viewer: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Option<ReelView>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT *
+                FROM reel_views
+                WHERE reel_id = $1 AND viewer = $2 AND last_seen_at >= $3
+                ORDER BY last_seen_at DESC
+                LIMIT 1
+            "#,
+        )
+        .bind(reel_id)
+        .bind(viewer)
+        .bind(since)
+        .fetch_optional(executor)
+        .await
+    }
+
+    pub async fn post_view_with<'e, E: PgExecutor<'e>>(&self, executor: E, view: &ReelView) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+                INSERT INTO reel_views (id, reel_id, viewer, user_id, started_at, last_seen_at, watch_ms, completion)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(view.id)
+        .bind(view.reel_id)
+        .bind(&view.viewer)
+        .bind(view.user_id)
+        .bind(view.started_at)
+        .bind(view.last_seen_at)
+        .bind(view.watch_ms)
+        .bind(view.completion)
+        .execute(executor)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn put_view_with<'e, E: PgExecutor<'e>>(&self, executor: E, view: &ReelView) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+                UPDATE reel_views
+                SET last_seen_at = $2,
+                    watch_ms = $3,
+                    completion = $4
+                WHERE id = $1
+            "#,
+        )
+        .bind(view.id)
+        .bind(view.last_seen_at)
+        .bind(view.watch_ms)
+        .bind(view.completion)
+        .execute(executor)
+        .await
+        .map(|_| ())
+    }
+
+    /// Adds to the view count and completion sum kept on the reel.
+    pub async fn add_reel_views_with<'e, E: PgExecutor<'e>>(
+        &self,
+        executor: E,
+        reel_id: Uuid,
+        views: i64,
+        completion: f64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+                UPDATE reels
+                SET view_count = view_count + $2,
+                    completion_sum = completion_sum + $3
+                WHERE id = $1
+            "#,
+        )
+        .bind(reel_id)
+        .bind(views)
+        .bind(completion)
+        .execute(executor)
+        .await
+        .map(|_| ())
+    }
+}
@@ -0,0 +1,180 @@
+use uuid::Uuid;
+
+use crate::model::MediaJob;
+
+use super::database_context::Table;
+
+impl<'c> Table<'c, MediaJob> {
+    pub async fn get_job_by_id(&self, job_id: Uuid) -> Result<MediaJob, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM media_jobs WHERE id = $1")
+            .bind(job_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn get_jobs_paginated(
+        &self,
+        status: Option<&str>,
+        kind: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<MediaJob>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT *
+                FROM media_jobs
+                WHERE ($1::varchar IS NULL OR status = $1)
+                  AND ($2::varchar IS NULL OR kind = $2)
+                ORDER BY created_at DESC
+                LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(status)
+        .bind(kind)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    pub async fn enqueue_job(
+        &self,
+        kind: &str,
+        payload: &serde_json::Value,
+        max_attempts: i32,
+    ) -> Result<Uuid, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+                INSERT INTO media_jobs (id, kind, payload, max_attempts)
+                VALUES ($1, $2, $3, $4)
+                RETURNING id
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(kind)
+        .bind(payload)
+        .bind(max_attempts)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    /// Queues a transcode for every video still waiting for renditions without one,
+    /// e.g. videos uploaded before the job queue existed.
+    pub async fn enqueue_missing_transcodes(&self, max_attempts: i32) -> Result<u64, sqlx::Error> {
+        sqlx::query(
+            r#"
+                INSERT INTO media_jobs (id, kind, payload, max_attempts)
+                SELECT gen_random_uuid(), 'transcode', jsonb_build_object('kind', 'transcode', 'video_id', v.id), $1
+                FROM videos v
+                WHERE v.processing_status IN ('pending', 'processing')
+                  AND NOT EXISTS (
+                      SELECT 1
+                      FROM media_jobs j
+                      WHERE j.kind = 'transcode'
+                        AND j.status IN ('queued', 'running')
+                        AND j.payload->>'video_id' = v.id::text
+                  )
+            "#,
+        )
+        .bind(max_attempts)
+        .execute(&*self.pool)
+        .await
+        .map(|x| x.rows_affected())
+    }
+
+    /// Takes the oldest due job, or a running one whose lease expired. `SKIP LOCKED`
+    /// lets several workers poll concurrently without handing out the same job twice.
+    pub async fn claim_job(&self, lease_seconds: i64) -> Result<Option<MediaJob>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                UPDATE media_jobs
+                SET status = 'running',
+                    attempts = attempts + 1,
+                    locked_at = now(),
+                    updated_at = now()
+                WHERE id = (
+                    SELECT id
+                    FROM media_jobs
+                    WHERE (status = 'queued' AND run_at <= now())
+                       OR (status = 'running' AND locked_at < now() - make_interval(secs => $1))
+                    ORDER BY run_at
+                    FOR UPDATE SKIP LOCKED
+                    LIMIT 1
+                )
+                RETURNING *
+            "#,
+        )
+        .bind(lease_seconds as f64)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    /// The outcome updates below only apply while the caller still holds the claim: a job whose
+    /// lease expired and was claimed again has a higher `attempts`, so a stale worker matches no row.
+    pub async fn complete_job(&self, job_id: Uuid, attempts: i32) -> Result<u64, sqlx::Error> {
+        sqlx::query(
+            r#"
+                UPDATE media_jobs
+                SET status = 'succeeded', locked_at = NULL, last_error = NULL, updated_at = now()
+                WHERE id = $1 AND status = 'running' AND attempts = $2
+            "#,
+        )
+        .bind(job_id)
+        .bind(attempts)
+        .execute(&*self.pool)
+        .await
+        .map(|x| x.rows_affected())
+    }
+
+    pub async fn retry_job_later(&self, job_id: Uuid, attempts: i32, error: &str, delay_seconds: i64) -> Result<u64, sqlx::Error> {
+        sqlx::query(
+            r#"
+                UPDATE media_jobs
+                SET status = 'queued',
+                    run_at = now() + make_interval(secs => $4),
+                    locked_at = NULL,
+                    last_error = $3,
+                    updated_at = now()
+                WHERE id = $1 AND status = 'running' AND attempts = $2
+            "#,
+        )
+        .bind(job_id)
+        .bind(attempts)
+        .bind(error)
+        .bind(delay_seconds as f64)
+        .execute(&*self.pool)
+        .await
+        .map(|x| x.rows_affected())
+    }
+
+    pub async fn bury_job(&self, job_id: Uuid, attempts: i32, error: &str) -> Result<u64, sqlx::Error> {
+        sqlx::query(
+            r#"
+                UPDATE media_jobs
+                SET status = 'dead', locked_at = NULL, last_error = $3, updated_at = now()
+                WHERE id = $1 AND status = 'running' AND attempts = $2
+            "#,
+        )
+        .bind(job_id)
+        .bind(attempts)
+        .bind(error)
+        .execute(&*self.pool)
+        .await
+        .map(|x| x.rows_affected())
+    }
+
+    /// Requeues a dead or waiting job to run now with a fresh set of attempts.
+    pub async fn retry_job(&self, job_id: Uuid) -> Result<Option<MediaJob>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                UPDATE media_jobs
+                SET status = 'queued', attempts = 0, run_at = now(), locked_at = NULL, updated_at = now()
+                WHERE id = $1 AND status IN ('dead', 'queued')
+                RETURNING *
+            "#,
+        )
+        .bind(job_id)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+}
@@ -1,4 +1,13 @@
 pub mod database_context;
 
+mod audit_dao;
+mod comment_dao;
+mod engagement_dao;
+mod event_dao;
+mod job_dao;
+mod recipe_video_dao;
 mod reel_dao;
+mod stats_dao;
+mod tag_dao;
+mod upload_dao;
 mod video_dao;
@@ -0,0 +1,80 @@
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::model::{RecipeSummary, RecipeVideo, Video};
+
+use super::database_context::Table;
+
+impl<'c> Table<'c, RecipeVideo> {
+    pub async fn recipe_exists(&self, recipe_id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM recipes WHERE id = $1)")
+            .bind(recipe_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    /// Links the video to the recipe. Linking it again keeps the existing row.
+    pub async fn post_recipe_video_with<'e, E: PgExecutor<'e>>(
+        &self,
+        executor: E,
+        recipe_id: Uuid,
+        video_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+                INSERT INTO recipe_videos (recipe_id, video_id)
+                VALUES ($1, $2)
+                ON CONFLICT (recipe_id, video_id) DO NOTHING
+            "#,
+        )
+        .bind(recipe_id)
+        .bind(video_id)
+        .execute(executor)
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn post_recipe_video(&self, recipe_id: Uuid, video_id: Uuid) -> Result<(), sqlx::Error> {
+        self.post_recipe_video_with(&*self.pool, recipe_id, video_id).await
+    }
+
+    pub async fn delete_recipe_video(&self, recipe_id: Uuid, video_id: Uuid) -> Result<u64, sqlx::Error> {
+        sqlx::query("DELETE FROM recipe_videos WHERE recipe_id = $1 AND video_id = $2")
+            .bind(recipe_id)
+            .bind(video_id)
+            .execute(&*self.pool)
+            .await
+            .map(|x| x.rows_affected())
+    }
+
+    /// Videos of the recipe in the order they were linked.
+    pub async fn get_videos_by_recipe_id(&self, recipe_id: Uuid) -> Result<Vec<Video>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT v.*
+                FROM recipe_videos rv
+                JOIN videos v ON v.id = rv.video_id
+                WHERE rv.recipe_id = $1
+                ORDER BY rv.created_at, v.id
+            "#,
+        )
+        .bind(recipe_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    pub async fn get_recipes_by_video_id(&self, video_id: Uuid) -> Result<Vec<RecipeSummary>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT r.id, r.name
+                FROM recipe_videos rv
+                JOIN recipes r ON r.id = rv.recipe_id
+                WHERE rv.video_id = $1
+                ORDER BY rv.created_at, r.id
+            "#,
+        )
+        .bind(video_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+}
@@ -1,7 +1,8 @@
+use sqlx::PgExecutor;
 use sqlx::types::chrono::Utc;
 use uuid::Uuid;
 
-use crate::model::{Reel, ReelWithVideos, Video};
+use crate::model::{PostReel, Reel, ReelCursor, ReelSearch, ReelSearchHit, ReelWithVideo, ReelWithVideos, Video};
 
 use super::database_context::Table;
 
@@ -37,6 +38,21 @@ impl<'c> Table<'c, Reel> {
         .await
     }
 
+    /// Ids and titles of every reel of the user, newest first.
+    pub async fn get_reel_titles_by_user_id(&self, user_id: Uuid) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT id, title
+                FROM reels
+                WHERE posting_user_id = $1
+                ORDER BY creation_timestamp DESC, id DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
     pub async fn get_reels_paginated(
         &self,
         offset: i64,
@@ -46,7 +62,7 @@ impl<'c> Table<'c, Reel> {
             r#"
                 SELECT * 
                 FROM reels
-                ORDER BY creation_timestamp DESC
+                ORDER BY creation_timestamp DESC, id DESC
                 LIMIT $1 OFFSET $2
             "#,
         )
@@ -67,7 +83,7 @@ impl<'c> Table<'c, Reel> {
                 SELECT * 
                 FROM reels
                 WHERE posting_user_id = $1
-                ORDER BY creation_timestamp DESC
+                ORDER BY creation_timestamp DESC, id DESC
                 LIMIT $2 OFFSET $3
             "#,
         )
@@ -87,7 +103,7 @@ impl<'c> Table<'c, Reel> {
             r#"
                 SELECT * 
                 FROM reels
-                ORDER BY creation_timestamp DESC
+                ORDER BY creation_timestamp DESC, id DESC
                 LIMIT $1 OFFSET $2
             "#,
         )
@@ -112,17 +128,153 @@ impl<'c> Table<'c, Reel> {
         .await?;
         
         Ok(ReelWithVideos {
-            reels: reels,
-            videos: videos,
+            reels,
+            videos,
+            next_cursor: None,
         })
     }
 
-    pub async fn post_reel(&self, reel: &Reel) -> Result<u64, sqlx::Error> {
+    /// Reels older than `cursor`, or the newest ones without it.
+    pub async fn get_reels_after_cursor(
+        &self,
+        cursor: Option<&ReelCursor>,
+        limit: i64,
+    ) -> Result<Vec<Reel>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT *
+                FROM reels
+                WHERE $1::timestamp IS NULL OR (creation_timestamp, id) < ($1, $2)
+                ORDER BY creation_timestamp DESC, id DESC
+                LIMIT $3
+            "#,
+        )
+        .bind(cursor.map(|c| c.creation_timestamp))
+        .bind(cursor.map(|c| c.id))
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    pub async fn get_reels_by_user_id_after_cursor(
+        &self,
+        user_id: Uuid,
+        cursor: Option<&ReelCursor>,
+        limit: i64,
+    ) -> Result<Vec<Reel>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT *
+                FROM reels
+                WHERE posting_user_id = $1
+                    AND ($2::timestamp IS NULL OR (creation_timestamp, id) < ($2, $3))
+                ORDER BY creation_timestamp DESC, id DESC
+                LIMIT $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(cursor.map(|c| c.creation_timestamp))
+        .bind(cursor.map(|c| c.id))
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Reels older than `cursor` joined with their videos. Reels whose video is gone are left out.
+    pub async fn get_feed_after_cursor(
+        &self,
+        cursor: Option<&ReelCursor>,
+        limit: i64,
+    ) -> Result<Vec<ReelWithVideo>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT v.*,
+                    r.id AS reel_id,
+                    r.video_id AS reel_video_id,
+                    r.posting_user_id AS reel_posting_user_id,
+                    r.title AS reel_title,
+                    r.description AS reel_description,
+                    r.creation_timestamp AS reel_creation_timestamp,
+                    r.like_count AS reel_like_count,
+                    r.save_count AS reel_save_count,
+                    r.view_count AS reel_view_count,
+                    r.completion_sum AS reel_completion_sum
+                FROM reels r
+                JOIN videos v ON v.id = r.video_id
+                WHERE $1::timestamp IS NULL OR (r.creation_timestamp, r.id) < ($1, $2)
+                ORDER BY r.creation_timestamp DESC, r.id DESC
+                LIMIT $3
+            "#,
+        )
+        .bind(cursor.map(|c| c.creation_timestamp))
+        .bind(cursor.map(|c| c.id))
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Reels matching `tsquery` ordered by rank, then like the feed. Snippets are only built for the returned page.
+    pub async fn search_reels(
+        &self,
+        tsquery: &str,
+        search: &ReelSearch,
+        cursor: Option<&ReelCursor>,
+        limit: i64,
+    ) -> Result<Vec<ReelSearchHit>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT hits.*,
+                    ts_headline('simple', translate(coalesce(hits.title, ''), $9, ''),
+                        to_tsquery('simple', $1),
+                        'StartSel=' || $10 || ', StopSel=' || $11 || ', HighlightAll=true') AS title_highlight,
+                    ts_headline('simple', translate(coalesce(hits.description, ''), $9, ''),
+                        to_tsquery('simple', $1),
+                        'StartSel=' || $10 || ', StopSel=' || $11
+                            || ', MaxFragments=2, MinWords=10, MaxWords=30, FragmentDelimiter=" ... "') AS description_highlight
+                FROM (
+                    SELECT r.id, r.video_id, r.posting_user_id, r.title, r.description, r.creation_timestamp,
+                        r.like_count, r.save_count, r.view_count, r.completion_sum,
+                        ts_rank(r.search_vector, to_tsquery('simple', $1)) AS rank
+                    FROM reels r
+                    WHERE r.search_vector @@ to_tsquery('simple', $1)
+                        AND ($2::uuid IS NULL OR r.posting_user_id = $2)
+                        AND ($3::timestamp IS NULL OR r.creation_timestamp >= $3)
+                        AND ($4::timestamp IS NULL OR r.creation_timestamp < $4)
+                        AND ($5::real IS NULL
+                            OR (ts_rank(r.search_vector, to_tsquery('simple', $1)), r.creation_timestamp, r.id) < ($5, $6, $7))
+                    ORDER BY rank DESC, r.creation_timestamp DESC, r.id DESC
+                    LIMIT $8
+                ) hits
+                ORDER BY hits.rank DESC, hits.creation_timestamp DESC, hits.id DESC
+            "#,
+        )
+        .bind(tsquery)
+        .bind(search.posting_user_id)
+        .bind(search.from)
+        .bind(search.to)
+        .bind(cursor.and_then(|c| c.rank))
+        .bind(cursor.map(|c| c.creation_timestamp))
+        .bind(cursor.map(|c| c.id))
+        .bind(limit)
+        .bind(format!("{}{}", ReelSearchHit::HIGHLIGHT_START, ReelSearchHit::HIGHLIGHT_STOP))
+        .bind(ReelSearchHit::HIGHLIGHT_START.to_string())
+        .bind(ReelSearchHit::HIGHLIGHT_STOP.to_string())
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    pub async fn post_reel(&self, reel: &Reel) -> Result<Reel, sqlx::Error> {
         let _ = self.create_table().await;
-        sqlx::query(
+
+        self.post_reel_with(&*self.pool, reel).await
+    }
+
+    pub async fn post_reel_with<'e, E: PgExecutor<'e>>(&self, executor: E, reel: &Reel) -> Result<Reel, sqlx::Error> {
+        sqlx::query_as(
             r#"
                 INSERT INTO reels (id, video_id, posting_user_id, title, description, creation_timestamp)
                 VALUES($1, $2, $3, $4, $5, $6)
+                RETURNING *
             "#
         )
             .bind(reel.id)               
@@ -131,12 +283,35 @@ impl<'c> Table<'c, Reel> {
             .bind(reel.title.clone())
             .bind(reel.description.clone())
             .bind(Utc::now().naive_utc())
-            .execute(&*self.pool) 
+            .fetch_one(executor)
             .await
-            .map(|x| x.rows_affected())
     }
 
-    pub async fn delete_reel(&self, reel_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+    pub async fn put_reel(&self, reel_id: Uuid, reel: &PostReel) -> Result<Reel, sqlx::Error> {
+        self.put_reel_with(&*self.pool, reel_id, reel).await
+    }
+
+    pub async fn put_reel_with<'e, E: PgExecutor<'e>>(&self, executor: E, reel_id: Uuid, reel: &PostReel) -> Result<Reel, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                UPDATE reels
+                SET title = $2,
+                    description = $3,
+                    video_id = COALESCE($4, video_id)
+                WHERE id = $1
+                RETURNING *
+            "#
+        )
+        .bind(reel_id)
+        .bind(reel.title.clone())
+        .bind(reel.description.clone())
+        .bind(reel.video_id)
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Removes the reel and its video. Tags, likes, saves and comments go with the reel through their foreign keys.
+    pub async fn delete_reel(&self, reel_id: Uuid) -> Result<Option<Video>, sqlx::Error> {
         let mut tx = self.pool.begin().await?;
 
         let video_id: Option<(Uuid,)> = sqlx::query_as::<_, (Uuid,)>(
@@ -147,25 +322,18 @@ impl<'c> Table<'c, Reel> {
         .await?;
     
         if let Some((video_id,)) = video_id {
-            let video_url: Option<(String,)> = sqlx::query_as::<_, (String,)>(
-                "SELECT video_url FROM videos WHERE id = $1"
-            )
-            .bind(video_id)
-            .fetch_optional(&mut *tx)
-            .await?;
-    
             sqlx::query("DELETE FROM reels WHERE id = $1")
                 .bind(reel_id)
                 .execute(&mut *tx)
                 .await?;
     
-            sqlx::query("DELETE FROM videos WHERE id = $1")
+            let video: Option<Video> = sqlx::query_as("DELETE FROM videos WHERE id = $1 RETURNING *")
                 .bind(video_id)
-                .execute(&mut *tx)
+                .fetch_optional(&mut *tx)
                 .await?;
     
             tx.commit().await?;
-            Ok(video_url.map(|v| v.0))
+            Ok(video)
         } else {
             tx.commit().await?;
             Ok(None)
@@ -0,0 +1,176 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use crate::model::ReelDailyStats;
+
+use super::database_context::Table;
+
+impl<'c> Table<'c, ReelDailyStats> {
+    /// Rebuilds the rollups of the days from `since` on, or of every day when `since` is `None`.
+    /// Views count on the day they started. Returns the number of reel days written.
+    pub async fn refresh_daily_stats(&self, since: Option<NaiveDate>) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM reel_daily_viewers WHERE $1::date IS NULL OR day >= $1")
+            .bind(since)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+                INSERT INTO reel_daily_viewers (reel_id, day, viewer)
+                SELECT DISTINCT reel_id, (started_at AT TIME ZONE 'UTC')::date, viewer
+                FROM reel_views
+                WHERE $1::date IS NULL OR started_at >= $1::timestamp AT TIME ZONE 'UTC'
+            "#,
+        )
+        .bind(since)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM reel_daily_stats WHERE $1::date IS NULL OR day >= $1")
+            .bind(since)
+            .execute(&mut *tx)
+            .await?;
+        let written = sqlx::query(
+            r#"
+                WITH since AS (
+                    SELECT $1::timestamp AT TIME ZONE 'UTC' AS at
+                ), views AS (
+                    SELECT reel_id, (started_at AT TIME ZONE 'UTC')::date AS day,
+                        count(*) AS views, count(DISTINCT viewer) AS unique_viewers, sum(completion) AS completion_sum
+                    FROM reel_views, since
+                    WHERE since.at IS NULL OR started_at >= since.at
+                    GROUP BY 1, 2
+                ), likes AS (
+                    SELECT reel_id, (created_at AT TIME ZONE 'UTC')::date AS day, count(*) AS likes
+                    FROM reel_likes, since
+                    WHERE since.at IS NULL OR created_at >= since.at
+                    GROUP BY 1, 2
+                ), saves AS (
+                    SELECT reel_id, (created_at AT TIME ZONE 'UTC')::date AS day, count(*) AS saves
+                    FROM reel_saves, since
+                    WHERE since.at IS NULL OR created_at >= since.at
+                    GROUP BY 1, 2
+                ), comments AS (
+                    SELECT reel_id, (created_at AT TIME ZONE 'UTC')::date AS day, count(*) AS comments
+                    FROM reel_comments, since
+                    WHERE deleted_at IS NULL AND (since.at IS NULL OR created_at >= since.at)
+                    GROUP BY 1, 2
+                ), days AS (
+                    SELECT reel_id, day FROM views
+                    UNION SELECT reel_id, day FROM likes
+                    UNION SELECT reel_id, day FROM saves
+                    UNION SELECT reel_id, day FROM comments
+                )
+                INSERT INTO reel_daily_stats (reel_id, day, views, unique_viewers, completion_sum, likes, saves, comments, refreshed_at)
+                SELECT d.reel_id, d.day,
+                    COALESCE(v.views, 0), COALESCE(v.unique_viewers, 0), COALESCE(v.completion_sum, 0),
+                    COALESCE(l.likes, 0), COALESCE(s.saves, 0), COALESCE(c.comments, 0), now()
+                FROM days d
+                LEFT JOIN views v USING (reel_id, day)
+                LEFT JOIN likes l USING (reel_id, day)
+                LEFT JOIN saves s USING (reel_id, day)
+                LEFT JOIN comments c USING (reel_id, day)
+            "#,
+        )
+        .bind(since)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+        Ok(written)
+    }
+
+    /// Rollups of the reels of the user between `from` and `to`, both included.
+    pub async fn get_daily_stats_by_user_id(
+        &self,
+        user_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<ReelDailyStats>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT s.*
+                FROM reel_daily_stats s
+                JOIN reels r ON r.id = s.reel_id
+                WHERE r.posting_user_id = $1 AND s.day BETWEEN $2 AND $3
+                ORDER BY s.day
+            "#,
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Distinct viewers of each reel of the user over the whole range.
+    pub async fn get_unique_viewers_by_reel(
+        &self,
+        user_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(Uuid, i64)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT v.reel_id, count(DISTINCT v.viewer)
+                FROM reel_daily_viewers v
+                JOIN reels r ON r.id = v.reel_id
+                WHERE r.posting_user_id = $1 AND v.day BETWEEN $2 AND $3
+                GROUP BY v.reel_id
+            "#,
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Distinct viewers of any reel of the user, per day.
+    pub async fn get_unique_viewers_by_day(
+        &self,
+        user_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, i64)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT v.day, count(DISTINCT v.viewer)
+                FROM reel_daily_viewers v
+                JOIN reels r ON r.id = v.reel_id
+                WHERE r.posting_user_id = $1 AND v.day BETWEEN $2 AND $3
+                GROUP BY v.day
+            "#,
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Distinct viewers of any reel of the user over the whole range.
+    pub async fn get_unique_viewers(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+                SELECT count(DISTINCT v.viewer)
+                FROM reel_daily_viewers v
+                JOIN reels r ON r.id = v.reel_id
+                WHERE r.posting_user_id = $1 AND v.day BETWEEN $2 AND $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    pub async fn get_last_refresh(&self) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        sqlx::query_scalar("SELECT max(refreshed_at) FROM reel_daily_stats")
+            .fetch_one(&*self.pool)
+            .await
+    }
+}
@@ -0,0 +1,104 @@
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::model::{Reel, ReelCursor, Tag, TagCount};
+
+use super::database_context::Table;
+
+impl<'c> Table<'c, Tag> {
+    /// Makes `names` the tags of the reel, creating the ones that do not exist yet. `names` must not repeat.
+    pub async fn set_reel_tags_with<'e, E: PgExecutor<'e>>(
+        &self,
+        executor: E,
+        reel_id: Uuid,
+        names: &[String],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+                WITH wanted AS (
+                    -- DO UPDATE so existing tags are returned too
+                    INSERT INTO tags (name)
+                    SELECT unnest($2::text[])
+                    ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+                    RETURNING id
+                ), removed AS (
+                    DELETE FROM reel_tags
+                    WHERE reel_id = $1 AND tag_id NOT IN (SELECT id FROM wanted)
+                )
+                INSERT INTO reel_tags (reel_id, tag_id)
+                SELECT $1, id FROM wanted
+                ON CONFLICT (reel_id, tag_id) DO NOTHING
+            "#,
+        )
+        .bind(reel_id)
+        .bind(names)
+        .execute(executor)
+        .await
+        .map(|_| ())
+    }
+
+    /// Reels carrying the tag, ordered like the feed.
+    pub async fn get_reels_by_tag_after_cursor(
+        &self,
+        name: &str,
+        cursor: Option<&ReelCursor>,
+        limit: i64,
+    ) -> Result<Vec<Reel>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT r.*
+                FROM tags t
+                JOIN reel_tags rt ON rt.tag_id = t.id
+                JOIN reels r ON r.id = rt.reel_id
+                WHERE t.name = $1
+                    AND ($2::timestamp IS NULL OR (r.creation_timestamp, r.id) < ($2, $3))
+                ORDER BY r.creation_timestamp DESC, r.id DESC
+                LIMIT $4
+            "#,
+        )
+        .bind(name)
+        .bind(cursor.map(|c| c.creation_timestamp))
+        .bind(cursor.map(|c| c.id))
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Tags starting with `prefix`, most used first. `prefix` is a LIKE pattern and must be escaped.
+    pub async fn get_tags_by_prefix(&self, prefix: &str, limit: i64) -> Result<Vec<TagCount>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT t.name, count(rt.reel_id) AS reel_count
+                FROM tags t
+                LEFT JOIN reel_tags rt ON rt.tag_id = t.id
+                WHERE t.name LIKE $1 || '%'
+                GROUP BY t.id, t.name
+                ORDER BY reel_count DESC, t.name
+                LIMIT $2
+            "#,
+        )
+        .bind(prefix)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    /// Tags put on the most reels during the last `hours`.
+    pub async fn get_trending_tags(&self, hours: i32, limit: i64) -> Result<Vec<TagCount>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT t.name, count(*) AS reel_count
+                FROM reel_tags rt
+                JOIN tags t ON t.id = rt.tag_id
+                WHERE rt.created_at >= now() - make_interval(hours => $1)
+                GROUP BY t.id, t.name
+                ORDER BY reel_count DESC, t.name
+                LIMIT $2
+            "#,
+        )
+        .bind(hours)
+        .bind(limit)
+        .fetch_all(&*self.pool)
+        .await
+    }
+}
@@ -0,0 +1,108 @@
+use uuid::Uuid;
+
+use crate::model::TusUpload;
+
+use super::database_context::Table;
+
+impl<'c> Table<'c, TusUpload> {
+    pub async fn get_upload_by_id(&self, upload_id: Uuid) -> Result<TusUpload, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM tus_uploads WHERE id = $1 AND expires_at > now()")
+            .bind(upload_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    /// Marks the upload as being written for `lock_seconds`. Returns `None` when another request holds it.
+    pub async fn claim_upload(&self, upload_id: Uuid, lock_seconds: i64) -> Result<Option<TusUpload>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                UPDATE tus_uploads
+                SET locked_until = now() + make_interval(secs => $2)
+                WHERE id = $1 AND expires_at > now() AND (locked_until IS NULL OR locked_until < now())
+                RETURNING *
+            "#,
+        )
+        .bind(upload_id)
+        .bind(lock_seconds as f64)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    pub async fn release_upload(&self, upload_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE tus_uploads SET locked_until = NULL WHERE id = $1")
+            .bind(upload_id)
+            .execute(&*self.pool)
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn post_upload(&self, upload: &TusUpload) -> Result<TusUpload, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                INSERT INTO tus_uploads
+                    (id, posting_user_id, upload_length, upload_offset, metadata, file_name,
+                     content_type, video_metadata, expires_at)
+                VALUES ($1, $2, $3, 0, $4, $5, $6, $7, $8)
+                RETURNING *
+            "#,
+        )
+        .bind(upload.id)
+        .bind(upload.posting_user_id)
+        .bind(upload.upload_length)
+        .bind(&upload.metadata)
+        .bind(&upload.file_name)
+        .bind(&upload.content_type)
+        .bind(&upload.video_metadata)
+        .bind(upload.expires_at)
+        .fetch_one(&*self.pool)
+        .await
+    }
+
+    /// Moves the offset forward and pushes back the expiration. Returns `None` when the offset
+    /// is no longer `from_offset`, i.e. another request appended to the upload in the meantime.
+    pub async fn advance_upload(
+        &self,
+        upload_id: Uuid,
+        from_offset: i64,
+        to_offset: i64,
+        expiration_hours: i64,
+    ) -> Result<Option<TusUpload>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                UPDATE tus_uploads
+                SET upload_offset = $3,
+                    expires_at = now() + make_interval(hours => $4::int)
+                WHERE id = $1 AND upload_offset = $2
+                RETURNING *
+            "#,
+        )
+        .bind(upload_id)
+        .bind(from_offset)
+        .bind(to_offset)
+        .bind(expiration_hours)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    pub async fn finish_upload(&self, upload_id: Uuid, video_id: Uuid) -> Result<TusUpload, sqlx::Error> {
+        sqlx::query_as("UPDATE tus_uploads SET video_id = $2 WHERE id = $1 RETURNING *")
+            .bind(upload_id)
+            .bind(video_id)
+            .fetch_one(&*self.pool)
+            .await
+    }
+
+    pub async fn delete_upload(&self, upload_id: Uuid) -> Result<u64, sqlx::Error> {
+        sqlx::query("DELETE FROM tus_uploads WHERE id = $1")
+            .bind(upload_id)
+            .execute(&*self.pool)
+            .await
+            .map(|x| x.rows_affected())
+    }
+
+    pub async fn delete_expired_uploads(&self) -> Result<Vec<TusUpload>, sqlx::Error> {
+        sqlx::query_as("DELETE FROM tus_uploads WHERE expires_at <= now() RETURNING *")
+            .fetch_all(&*self.pool)
+            .await
+    }
+}
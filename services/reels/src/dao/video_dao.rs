@@ -1,6 +1,10 @@
+use sqlx::PgExecutor;
 use uuid::Uuid;
 
-use crate::model::Video;
+use crate::{
+    media::{Artwork, MediaInfo},
+    model::{PostVideo, ProcessingStatus, Video},
+};
 
 use super::database_context::Table;
 
@@ -50,14 +54,33 @@ impl<'c> Table<'c, Video> {
         .await
     }
 
-    pub async fn post_video(&self, video: &Video) -> Result<Uuid, sqlx::Error> {
+    pub async fn get_videos_by_ids(&self, video_ids: &[Uuid]) -> Result<Vec<Video>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                SELECT *
+                FROM videos
+                WHERE id = ANY($1)
+            "#,
+        )
+        .bind(video_ids)
+        .fetch_all(&*self.pool)
+        .await
+    }
+
+    pub async fn post_video(&self, video: &Video) -> Result<Video, sqlx::Error> {
         let _ = self.create_table().await;
-    
-        let row: (Uuid,) = sqlx::query_as(
+
+        self.post_video_with(&*self.pool, video).await
+    }
+
+    pub async fn post_video_with<'e, E: PgExecutor<'e>>(&self, executor: E, video: &Video) -> Result<Video, sqlx::Error> {
+        sqlx::query_as(
             r#"
-                INSERT INTO videos (id, posting_user_id, title, description, video_length_seconds, video_url)
-                VALUES($1, $2, $3, $4, $5, $6)
-                RETURNING id
+                INSERT INTO videos (id, posting_user_id, title, description, video_length_seconds, video_url, storage_key,
+                    width, height, codec, bitrate, container_format, poster_key, poster_url, thumbnail_key, thumbnail_url,
+                    processing_status)
+                VALUES($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+                RETURNING *
             "#
         )
         .bind(video.id)
@@ -66,47 +89,200 @@ impl<'c> Table<'c, Video> {
         .bind(video.description.clone())
         .bind(video.video_length_seconds)
         .bind(video.video_url.clone())
+        .bind(video.storage_key.clone())
+        .bind(video.width)
+        .bind(video.height)
+        .bind(video.codec.clone())
+        .bind(video.bitrate)
+        .bind(video.container_format.clone())
+        .bind(video.poster_key.clone())
+        .bind(video.poster_url.clone())
+        .bind(video.thumbnail_key.clone())
+        .bind(video.thumbnail_url.clone())
+        .bind(video.processing_status.as_str())
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn put_video(&self, video_id: Uuid, video: &PostVideo) -> Result<Video, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                UPDATE videos
+                SET title = $2,
+                    description = $3,
+                    -- probed videos keep the length read from the file
+                    video_length_seconds = CASE
+                        WHEN container_format IS NULL THEN COALESCE($4, video_length_seconds)
+                        ELSE video_length_seconds
+                    END
+                WHERE id = $1
+                RETURNING *
+            "#
+        )
+        .bind(video_id)
+        .bind(video.title.clone())
+        .bind(video.description.clone())
+        .bind(video.video_length_seconds)
         .fetch_one(&*self.pool)
-        .await?;
-    
-        Ok(row.0)
+        .await
     }
 
-    pub async fn put_video(&self, video: Video) -> Result<u64, sqlx::Error> {
-        let _ = self.create_table().await;
-        sqlx::query(
+    pub async fn put_video_file(
+        &self,
+        video_id: Uuid,
+        video: Option<&PostVideo>,
+        video_url: &str,
+        storage_key: &str,
+        media: &MediaInfo,
+    ) -> Result<(Video, Video), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous: Video = sqlx::query_as("SELECT * FROM videos WHERE id = $1 FOR UPDATE")
+            .bind(video_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let updated: Video = sqlx::query_as(
             r#"
-                INSERT INTO videos (id, posting_user_id, title, description, video_length_seconds, video_url)
-                VALUES($1, $2, $3, $4, $5, $6)
+                UPDATE videos
+                SET title = COALESCE($2, title),
+                    description = COALESCE($3, description),
+                    video_length_seconds = COALESCE($7, $4, video_length_seconds),
+                    video_url = $5,
+                    storage_key = $6,
+                    width = $8,
+                    height = $9,
+                    codec = $10,
+                    bitrate = $11,
+                    container_format = $12,
+                    processing_status = 'pending',
+                    hls_prefix = NULL,
+                    stream_url = NULL
+                WHERE id = $1
+                RETURNING *
             "#
-        )   
-            .bind(video.posting_user_id)
-            .bind(&video.title)
-            .bind(&video.description)
-            .bind(video.video_length_seconds)
-            .execute(&*self.pool) 
-            .await
-            .map(|x| x.rows_affected())
+        )
+        .bind(video_id)
+        .bind(video.map(|v| v.title.clone()))
+        .bind(video.map(|v| v.description.clone()))
+        .bind(video.and_then(|v| v.video_length_seconds))
+        .bind(video_url)
+        .bind(storage_key)
+        .bind(media.length_seconds())
+        .bind(media.width)
+        .bind(media.height)
+        .bind(media.codec.clone())
+        .bind(media.bitrate)
+        .bind(&media.container_format)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok((updated, previous))
     }
 
-    pub async fn delete_video(&self, video_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+    /// Points the video at a new poster and thumbnail, returning the keys of the replaced images.
+    pub async fn put_video_artwork(
+        &self,
+        video_id: Uuid,
+        artwork: &Artwork,
+    ) -> Result<(Video, Vec<String>), sqlx::Error> {
         let mut tx = self.pool.begin().await?;
 
-        let video_url: Option<(String,)> =
-            sqlx::query_as::<_, (String,)>("SELECT video_url FROM videos WHERE id = $1")
+        let (old_poster_key, old_thumbnail_key): (Option<String>, Option<String>) =
+            sqlx::query_as("SELECT poster_key, thumbnail_key FROM videos WHERE id = $1 FOR UPDATE")
                 .bind(video_id)
-                .fetch_optional(&mut *tx)
+                .fetch_one(&mut *tx)
                 .await?;
 
-        if video_url.is_some() {
-            sqlx::query("DELETE FROM videos WHERE id = $1")
-                .bind(video_id)
-                .execute(&mut *tx)
-                .await?;
-        }
+        let updated: Video = sqlx::query_as(
+            r#"
+                UPDATE videos
+                SET poster_key = $2,
+                    poster_url = $3,
+                    thumbnail_key = $4,
+                    thumbnail_url = $5
+                WHERE id = $1
+                RETURNING *
+            "#
+        )
+        .bind(video_id)
+        .bind(&artwork.poster_key)
+        .bind(&artwork.poster_url)
+        .bind(&artwork.thumbnail_key)
+        .bind(&artwork.thumbnail_url)
+        .fetch_one(&mut *tx)
+        .await?;
 
         tx.commit().await?;
-        Ok(video_url.map(|v| v.0))
+        Ok((updated, old_poster_key.into_iter().chain(old_thumbnail_key).collect()))
+    }
+
+    /// Marks the video as being transcoded, or as failed when it has no stored file to transcode.
+    /// Returns `None` when it no longer exists.
+    pub async fn start_transcode(&self, video_id: Uuid) -> Result<Option<Video>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+                UPDATE videos
+                SET processing_status = CASE WHEN storage_key IS NULL THEN 'failed' ELSE 'processing' END
+                WHERE id = $1
+                RETURNING *
+            "#
+        )
+        .bind(video_id)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    /// Publishes the renditions made from `storage_key` and returns the prefix of the ones they replace.
+    /// Returns `None` when the video was deleted or its file replaced while transcoding.
+    pub async fn finish_transcode(
+        &self,
+        video_id: Uuid,
+        storage_key: &str,
+        hls_prefix: &str,
+        stream_url: &str,
+    ) -> Result<Option<Option<String>>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+                UPDATE videos v
+                SET processing_status = 'ready',
+                    hls_prefix = $3,
+                    stream_url = $4
+                FROM (SELECT id, hls_prefix FROM videos WHERE id = $1 FOR UPDATE) previous
+                WHERE v.id = previous.id AND v.storage_key = $2
+                RETURNING previous.hls_prefix
+            "#
+        )
+        .bind(video_id)
+        .bind(storage_key)
+        .bind(hls_prefix)
+        .bind(stream_url)
+        .fetch_optional(&*self.pool)
+        .await
+    }
+
+    /// Updates the status unless the file was replaced since the transcode started.
+    pub async fn set_processing_status(
+        &self,
+        video_id: Uuid,
+        storage_key: &str,
+        status: ProcessingStatus,
+    ) -> Result<u64, sqlx::Error> {
+        sqlx::query("UPDATE videos SET processing_status = $3 WHERE id = $1 AND storage_key = $2")
+            .bind(video_id)
+            .bind(storage_key)
+            .bind(status.as_str())
+            .execute(&*self.pool)
+            .await
+            .map(|x| x.rows_affected())
+    }
+
+    pub async fn delete_video(&self, video_id: Uuid) -> Result<Option<Video>, sqlx::Error> {
+        sqlx::query_as("DELETE FROM videos WHERE id = $1 RETURNING *")
+            .bind(video_id)
+            .fetch_optional(&*self.pool)
+            .await
         // let _ = self.create_table().await;
         // sqlx::query(
         //     r#"
@@ -1,22 +1,165 @@
-use actix_web::{ResponseError, HttpResponse};
+use actix_web::{
+    error::{JsonPayloadError, PathError, QueryPayloadError},
+    http::{header, StatusCode},
+    web, HttpResponse, ResponseError,
+};
+use log::{error, warn};
+use sqlx::error::ErrorKind;
 use thiserror::Error;
+use uuid::Uuid;
+
+use super::problem::{ErrorCode, Problem};
 
 #[derive(Debug, Error)]
 pub enum AppError {
-    #[error("Not found: {0}")]
-    NotFound(String),
-    #[error("Bad request: {0}")]
-    BadRequest(String),
+    #[error("Not found: {1}")]
+    NotFound(ErrorCode, String),
+    #[error("Bad request: {1}")]
+    BadRequest(ErrorCode, String),
+    #[error("Unauthorized: {1}")]
+    Unauthorized(ErrorCode, String),
+    #[error("Forbidden: {1}")]
+    Forbidden(ErrorCode, String),
+    #[error("Conflict: {1}")]
+    Conflict(ErrorCode, String),
+    #[error("Unsupported media type: {1}")]
+    UnsupportedMediaType(ErrorCode, String),
+    #[error("Payload too large: {1}")]
+    PayloadTooLarge(ErrorCode, String),
+    /// A dependency such as the database or object storage cannot be reached right now.
+    #[error("Unavailable: {0}")]
+    Unavailable(String),
     #[error("Internal error: {0}")]
     InternalError(String),
 }
 
+impl AppError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::NotFound(code, _)
+            | AppError::BadRequest(code, _)
+            | AppError::Unauthorized(code, _)
+            | AppError::Forbidden(code, _)
+            | AppError::Conflict(code, _)
+            | AppError::UnsupportedMediaType(code, _)
+            | AppError::PayloadTooLarge(code, _) => *code,
+            AppError::Unavailable(_) => ErrorCode::Unavailable,
+            AppError::InternalError(_) => ErrorCode::Internal,
+        }
+    }
+}
+
+/// Translates driver errors once for every service: missing rows become 404, constraint
+/// violations 409 or 400, and connection problems 503.
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        match &e {
+            sqlx::Error::RowNotFound => AppError::NotFound(ErrorCode::ResourceNotFound, "Resource not found".into()),
+            sqlx::Error::Database(db) => match db.kind() {
+                ErrorKind::UniqueViolation => {
+                    AppError::Conflict(ErrorCode::ResourceConflict, "Resource already exists".into())
+                }
+                ErrorKind::ForeignKeyViolation => AppError::Conflict(
+                    ErrorCode::ResourceConflict,
+                    "Resource refers to or is referenced by another resource".into(),
+                ),
+                ErrorKind::NotNullViolation | ErrorKind::CheckViolation => {
+                    AppError::BadRequest(ErrorCode::ResourceInvalid, "Resource violates a data constraint".into())
+                }
+                _ => AppError::InternalError(e.to_string()),
+            },
+            sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => {
+                AppError::Unavailable(e.to_string())
+            }
+            _ => AppError::InternalError(e.to_string()),
+        }
+    }
+}
+
 impl ResponseError for AppError {
-    fn error_response(&self) -> HttpResponse {
+    fn status_code(&self) -> StatusCode {
         match self {
-            AppError::NotFound(msg) => HttpResponse::NotFound().body(msg.to_string()),
-            AppError::BadRequest(msg) => HttpResponse::BadRequest().body(msg.to_string()),
-            AppError::InternalError(msg) => HttpResponse::InternalServerError().body(msg.to_string()),
+            AppError::NotFound(..) => StatusCode::NOT_FOUND,
+            AppError::BadRequest(..) => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized(..) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(..) => StatusCode::FORBIDDEN,
+            AppError::Conflict(..) => StatusCode::CONFLICT,
+            AppError::UnsupportedMediaType(..) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            AppError::PayloadTooLarge(..) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            AppError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let status = self.status_code();
+        let code = self.code();
+        let instance = format!("urn:uuid:{}", Uuid::new_v4());
+
+        // Details of server-side failures are logged, never sent to clients.
+        let detail = match self {
+            AppError::NotFound(_, msg)
+            | AppError::BadRequest(_, msg)
+            | AppError::Unauthorized(_, msg)
+            | AppError::Forbidden(_, msg)
+            | AppError::Conflict(_, msg)
+            | AppError::UnsupportedMediaType(_, msg)
+            | AppError::PayloadTooLarge(_, msg) => msg.clone(),
+            AppError::Unavailable(msg) => {
+                warn!("Service unavailable ({}): {}", instance, msg);
+                "Service temporarily unavailable".to_string()
+            }
+            AppError::InternalError(msg) => {
+                error!("Internal error ({}): {}", instance, msg);
+                "Internal server error".to_string()
+            }
+        };
+
+        let mut response = HttpResponse::build(status);
+        if let AppError::Unauthorized(..) = self {
+            response.insert_header((header::WWW_AUTHENTICATE, "Bearer"));
         }
+        response
+            .insert_header((header::CONTENT_TYPE, "application/problem+json"))
+            .json(Problem {
+                problem_type: format!("urn:reels:problem:{}", code),
+                title: status.canonical_reason().unwrap_or("Error").to_string(),
+                status: status.as_u16(),
+                detail,
+                instance,
+                code,
+            })
     }
-}
\ No newline at end of file
+}
+
+/// Reports malformed JSON bodies in the same format as every other error.
+pub fn json_config() -> web::JsonConfig {
+    web::JsonConfig::default().error_handler(|e, _| {
+        let error = match e {
+            JsonPayloadError::ContentType => AppError::UnsupportedMediaType(
+                ErrorCode::UnsupportedMediaType,
+                "Content-Type must be application/json".into(),
+            ),
+            JsonPayloadError::Overflow { limit } | JsonPayloadError::OverflowKnownLength { limit, .. } => {
+                AppError::PayloadTooLarge(
+                    ErrorCode::RequestTooLarge,
+                    format!("JSON body exceeds the limit of {} bytes", limit),
+                )
+            }
+            e => AppError::BadRequest(ErrorCode::InvalidJson, format!("Invalid JSON body: {}", e)),
+        };
+        error.into()
+    })
+}
+
+pub fn path_config() -> web::PathConfig {
+    web::PathConfig::default().error_handler(|e: PathError, _| {
+        AppError::BadRequest(ErrorCode::InvalidPath, format!("Invalid path parameter: {}", e)).into()
+    })
+}
+
+pub fn query_config() -> web::QueryConfig {
+    web::QueryConfig::default().error_handler(|e: QueryPayloadError, _| {
+        AppError::BadRequest(ErrorCode::InvalidQuery, format!("Invalid query string: {}", e)).into()
+    })
+}
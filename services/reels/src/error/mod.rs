@@ -1 +1,3 @@
-pub mod error;
\ No newline at end of file
+#[allow(clippy::module_inception)]
+pub mod error;
+pub mod problem;
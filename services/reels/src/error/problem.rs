@@ -0,0 +1,201 @@
+use std::fmt;
+
+use utoipa::{
+    openapi::{schema::{ObjectBuilder, Schema, Type}, RefOr},
+    PartialSchema, ToSchema,
+};
+
+/// Stable, machine-readable identifier of a failure. Clients branch on this rather than on `detail`.
+/// It serializes as [`ErrorCode::as_str`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCode {
+    MissingField,
+    InvalidMultipart,
+    InvalidJson,
+    InvalidPath,
+    InvalidQuery,
+    InvalidCursor,
+    RequestTooLarge,
+    UnsupportedMediaType,
+    Unauthorized,
+    Forbidden,
+    ResourceNotFound,
+    ResourceConflict,
+    ResourceInvalid,
+    ReelNotFound,
+    ReelForbidden,
+    VideoNotFound,
+    VideoForbidden,
+    VideoNotDecodable,
+    InvalidCover,
+    InvalidPosterOffset,
+    RecipeNotFound,
+    InvalidTag,
+    CommentNotFound,
+    CommentForbidden,
+    InvalidComment,
+    InvalidEvent,
+    UploadNotFound,
+    UploadForbidden,
+    UploadTooLarge,
+    InvalidUploadHeader,
+    InvalidUploadMetadata,
+    UploadOffsetMismatch,
+    UploadLocked,
+    UnsupportedUploadMediaType,
+    UploadInterrupted,
+    JobNotFound,
+    JobNotRetryable,
+    Unavailable,
+    Internal,
+}
+
+impl ErrorCode {
+    /// Every code, in declaration order.
+    pub const ALL: [ErrorCode; 39] = [
+        ErrorCode::MissingField,
+        ErrorCode::InvalidMultipart,
+        ErrorCode::InvalidJson,
+        ErrorCode::InvalidPath,
+        ErrorCode::InvalidQuery,
+        ErrorCode::InvalidCursor,
+        ErrorCode::RequestTooLarge,
+        ErrorCode::UnsupportedMediaType,
+        ErrorCode::Unauthorized,
+        ErrorCode::Forbidden,
+        ErrorCode::ResourceNotFound,
+        ErrorCode::ResourceConflict,
+        ErrorCode::ResourceInvalid,
+        ErrorCode::ReelNotFound,
+        ErrorCode::ReelForbidden,
+        ErrorCode::VideoNotFound,
+        ErrorCode::VideoForbidden,
+        ErrorCode::VideoNotDecodable,
+        ErrorCode::InvalidCover,
+        ErrorCode::InvalidPosterOffset,
+        ErrorCode::RecipeNotFound,
+        ErrorCode::InvalidTag,
+        ErrorCode::CommentNotFound,
+        ErrorCode::CommentForbidden,
+        ErrorCode::InvalidComment,
+        ErrorCode::InvalidEvent,
+        ErrorCode::UploadNotFound,
+        ErrorCode::UploadForbidden,
+        ErrorCode::UploadTooLarge,
+        ErrorCode::InvalidUploadHeader,
+        ErrorCode::InvalidUploadMetadata,
+        ErrorCode::UploadOffsetMismatch,
+        ErrorCode::UploadLocked,
+        ErrorCode::UnsupportedUploadMediaType,
+        ErrorCode::UploadInterrupted,
+        ErrorCode::JobNotFound,
+        ErrorCode::JobNotRetryable,
+        ErrorCode::Unavailable,
+        ErrorCode::Internal,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::MissingField => "request.missing_field",
+            ErrorCode::InvalidMultipart => "request.invalid_multipart",
+            ErrorCode::InvalidJson => "request.invalid_json",
+            ErrorCode::InvalidPath => "request.invalid_path",
+            ErrorCode::InvalidQuery => "request.invalid_query",
+            ErrorCode::InvalidCursor => "request.invalid_cursor",
+            ErrorCode::RequestTooLarge => "request.too_large",
+            ErrorCode::UnsupportedMediaType => "request.unsupported_media_type",
+            ErrorCode::Unauthorized => "auth.unauthorized",
+            ErrorCode::Forbidden => "auth.forbidden",
+            ErrorCode::ResourceNotFound => "resource.not_found",
+            ErrorCode::ResourceConflict => "resource.conflict",
+            ErrorCode::ResourceInvalid => "resource.invalid",
+            ErrorCode::ReelNotFound => "reel.not_found",
+            ErrorCode::ReelForbidden => "reel.forbidden",
+            ErrorCode::VideoNotFound => "video.not_found",
+            ErrorCode::VideoForbidden => "video.forbidden",
+            ErrorCode::VideoNotDecodable => "video.not_decodable",
+            ErrorCode::InvalidCover => "video.invalid_cover",
+            ErrorCode::InvalidPosterOffset => "video.invalid_poster_offset",
+            ErrorCode::RecipeNotFound => "recipe.not_found",
+            ErrorCode::InvalidTag => "tag.invalid",
+            ErrorCode::CommentNotFound => "comment.not_found",
+            ErrorCode::CommentForbidden => "comment.forbidden",
+            ErrorCode::InvalidComment => "comment.invalid",
+            ErrorCode::InvalidEvent => "event.invalid",
+            ErrorCode::UploadNotFound => "upload.not_found",
+            ErrorCode::UploadForbidden => "upload.forbidden",
+            ErrorCode::UploadTooLarge => "upload.too_large",
+            ErrorCode::InvalidUploadHeader => "upload.invalid_header",
+            ErrorCode::InvalidUploadMetadata => "upload.invalid_metadata",
+            ErrorCode::UploadOffsetMismatch => "upload.offset_mismatch",
+            ErrorCode::UploadLocked => "upload.locked",
+            ErrorCode::UnsupportedUploadMediaType => "upload.unsupported_media_type",
+            ErrorCode::UploadInterrupted => "upload.interrupted",
+            ErrorCode::JobNotFound => "job.not_found",
+            ErrorCode::JobNotRetryable => "job.not_retryable",
+            ErrorCode::Unavailable => "service.unavailable",
+            ErrorCode::Internal => "internal",
+        }
+    }
+}
+
+impl serde::Serialize for ErrorCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl PartialSchema for ErrorCode {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .enum_values(Some(ErrorCode::ALL.iter().map(ErrorCode::as_str)))
+            .into()
+    }
+}
+
+impl ToSchema for ErrorCode {}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error body sent as `application/problem+json` (RFC 7807).
+#[derive(serde::Serialize, Debug, ToSchema)]
+pub struct Problem {
+    /// URI identifying the kind of problem, derived from `code`.
+    #[serde(rename = "type")]
+    #[schema(example = "urn:reels:problem:reel.not_found")]
+    pub problem_type: String,
+    #[schema(example = "Not Found")]
+    pub title: String,
+    #[schema(example = 404)]
+    pub status: u16,
+    #[schema(example = "Reel not found")]
+    pub detail: String,
+    /// Identifies this occurrence; it is logged next to server-side failures.
+    #[schema(example = "urn:uuid:3fa85f64-5717-4562-b3fc-2c963f66afa6")]
+    pub instance: String,
+    pub code: ErrorCode,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_lists_every_code_in_order() {
+        for (i, code) in ErrorCode::ALL.iter().enumerate() {
+            assert_eq!(*code as usize, i);
+        }
+    }
+
+    #[test]
+    fn serializes_as_str() {
+        for code in ErrorCode::ALL {
+            assert_eq!(serde_json::to_value(code).unwrap(), serde_json::Value::from(code.as_str()));
+        }
+    }
+}
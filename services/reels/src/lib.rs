@@ -1,18 +1,31 @@
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
-use service::{reel_service::ReelService, video_service::VideoService};
+use service::{
+    comment_service::CommentService, engagement_service::EngagementService, event_service::EventService, job_service::JobService, reel_service::ReelService, stats_service::StatsService, tag_service::TagService, upload_service::UploadService,
+    video_service::VideoService,
+};
 
+pub mod auth;
 pub mod config;
 pub mod controller;
 pub mod dao;
+pub mod media;
 pub mod model;
 pub mod openapi;
 pub mod service;
+pub mod storage;
 pub mod util;
 pub mod error;
 
 pub struct AppState<'a> {
     pub connections: Mutex<u32>,
     pub reels_service: ReelService<'a>,
-    pub video_service: VideoService<'a>,
+    pub video_service: Arc<VideoService<'a>>,
+    pub job_service: JobService<'a>,
+    pub tag_service: TagService<'a>,
+    pub engagement_service: EngagementService<'a>,
+    pub comment_service: CommentService<'a>,
+    pub event_service: EventService<'a>,
+    pub stats_service: Arc<StatsService<'a>>,
+    pub upload_service: Arc<UploadService<'a>>,
 }
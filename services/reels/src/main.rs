@@ -2,12 +2,22 @@ use actix_web::web::Data;
 use actix_web::{App, HttpServer, web};
 use actix_files::Files;
 use actix_cors::Cors;
-use reels_microservice::config::{Settings, get_configuration};
+use reels_microservice::auth::JwtValidator;
+use reels_microservice::config::{Settings, StorageSettings, get_configuration};
 use reels_microservice::dao::database_context::Database;
+use reels_microservice::error::{error::{self as app_error, AppError}, problem::ErrorCode};
 use reels_microservice::openapi::ApiDoc;
+use reels_microservice::service::comment_service::{CommentRepository, CommentService};
+use reels_microservice::service::engagement_service::{EngagementRepository, EngagementService};
+use reels_microservice::service::event_service::{EventRepository, EventService};
 use reels_microservice::service::reel_service::{ReelRepository, ReelService};
+use reels_microservice::service::job_service::{JobQueue, JobRepository, JobService, JobWorker};
+use reels_microservice::service::stats_service::{StatsRepository, StatsService};
+use reels_microservice::service::tag_service::{TagRepository, TagService};
+use reels_microservice::service::transcode_service::TranscodeService;
+use reels_microservice::service::upload_service::{UploadRepository, UploadService};
 use reels_microservice::service::video_service::{VideoRepository, VideoService};
-use reels_microservice::{AppState, controller};
+use reels_microservice::{AppState, controller, media, storage};
 use std::sync::{Arc, Mutex};
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
@@ -21,32 +31,123 @@ async fn main() -> std::io::Result<()> {
 
     let db_context: Arc<Database<'_>> =
         Arc::new(Database::new(&configuration.database.connection_string()).await);
-    let reel_service: ReelService<'_> = ReelService::new(db_context.clone());
-    let video_service: VideoService<'_> = VideoService::new(db_context);
+    let video_storage = storage::from_settings(&configuration.storage);
+    let job_queue = JobQueue::new(&db_context, &configuration.jobs);
+    let video_service: Arc<VideoService<'_>> = Arc::new(VideoService::new(
+        db_context.clone(),
+        configuration.upload.clone(),
+        configuration.media.clone(),
+        video_storage.clone(),
+        media::probe_from_settings(&configuration.media),
+        media::frames_from_settings(&configuration.media),
+        job_queue.clone(),
+    ));
+    let reel_service: ReelService<'_> = ReelService::new(db_context.clone(), job_queue, video_service.clone());
+    let job_service: JobService<'_> = JobService::new(db_context.clone());
+    let tag_service: TagService<'_> = TagService::new(db_context.clone());
+    let engagement_service: EngagementService<'_> = EngagementService::new(db_context.clone());
+    let comment_service: CommentService<'_> = CommentService::new(db_context.clone());
+    let event_service: EventService<'_> = EventService::new(db_context.clone());
+    let stats_service: Arc<StatsService<'_>> = Arc::new(StatsService::new(db_context.clone(), configuration.stats.clone()));
+    stats_service.clone().start_refresher();
+    let upload_service: Arc<UploadService<'_>> = Arc::new(UploadService::new(
+        db_context.clone(),
+        configuration.upload.clone(),
+        configuration.tus.clone(),
+        video_service.clone(),
+    ));
+    upload_service.clone().start_sweeper();
+
+    // Media jobs run on the same runtime as the HttpServer.
+    JobWorker::new(
+        db_context.clone(),
+        configuration.jobs.clone(),
+        video_storage.clone(),
+        video_service.clone(),
+        TranscodeService::new(
+            db_context,
+            video_storage,
+            media::probe_from_settings(&configuration.media),
+            media::transcoder_from_settings(&configuration.media, &configuration.transcoding),
+            configuration.upload.clone(),
+            configuration.transcoding.clone(),
+        ),
+    )
+    .start()
+    .await;
+
+    let jwt_validator: Data<JwtValidator> = web::Data::new(JwtValidator::from_settings(configuration.auth.clone()).await);
 
     let app_state: Data<AppState<'_>> = web::Data::new(AppState {
         connections: Mutex::new(0),
         reels_service: reel_service,
-        video_service: video_service,
-    });    let app = HttpServer::new(move || {
+        video_service,
+        job_service,
+        tag_service,
+        engagement_service,
+        comment_service,
+        event_service,
+        stats_service,
+        upload_service,
+    });
+
+    let app = HttpServer::new(move || {
         App::new()
             .wrap(
                 Cors::default()
                     .allowed_origin("http://localhost:8020")
                     .allowed_origin("http://localhost:8000")
-                    .allowed_methods(vec!["GET", "POST", "PUT", "DELETE", "OPTIONS"])
-                    .allowed_headers(vec!["Content-Type", "Authorization", "Accept"])
+                    .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "HEAD", "DELETE", "OPTIONS"])
+                    .allowed_headers(vec![
+                        "Content-Type",
+                        "Authorization",
+                        "Accept",
+                        "Tus-Resumable",
+                        "Upload-Length",
+                        "Upload-Offset",
+                        "Upload-Metadata",
+                    ])
+                    .expose_headers(vec![
+                        "Location",
+                        "Tus-Resumable",
+                        "Upload-Offset",
+                        "Upload-Length",
+                        "Upload-Expires",
+                        "x-video-id",
+                    ])
                     .supports_credentials()
             )
             .app_data(app_state.clone())
+            .app_data(jwt_validator.clone())
+            .app_data(app_error::json_config())
+            .app_data(app_error::path_config())
+            .app_data(app_error::query_config())
             .configure(controller::init_health_controller)
             .configure(controller::init_reel_controller)
             .configure(controller::init_video_controller)
-            .service(Files::new("/static/videos", "./upload").show_files_listing())
+            .configure(controller::init_recipe_controller)
+            .configure(controller::init_engagement_controller)
+            .configure(controller::init_comment_controller)
+            .configure(controller::init_event_controller)
+            .configure(controller::init_stats_controller)
+            .configure(controller::init_tag_controller)
+            .configure(controller::init_upload_controller)
+            .configure(controller::init_admin_controller)
+            .configure(|cfg| {
+                if let StorageSettings::Local(local) = &configuration.storage {
+                    cfg.service(Files::new(&local.public_url, &local.directory).show_files_listing());
+                }
+            })
             .service(
                 SwaggerUi::new("/swagger-ui/{_:.*}")
                     .url("/api-docs/openapi.json", ApiDoc::openapi()),
             )
+            .default_service(web::to(|| async {
+                Err::<actix_web::HttpResponse, _>(AppError::NotFound(
+                    ErrorCode::ResourceNotFound,
+                    "No such endpoint".into(),
+                ))
+            }))
     })
     .bind(adress)?;
 
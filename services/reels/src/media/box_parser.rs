@@ -0,0 +1,608 @@
+use std::{
+    fs::File,
+    io::{self, Cursor, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use async_trait::async_trait;
+
+use super::{normalize_codec, MediaInfo, MediaProbe, ProbeError};
+
+/// Reads container metadata directly from MP4 (ISO BMFF) boxes or WebM/Matroska
+/// EBML elements. Used when `ffprobe` is not installed.
+pub struct BoxParserProbe;
+
+#[async_trait]
+impl MediaProbe for BoxParserProbe {
+    async fn probe(&self, path: &Path) -> Result<MediaInfo, ProbeError> {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || parse_file(&path))
+            .await
+            .map_err(|e| ProbeError::Unavailable(e.to_string()))?
+    }
+}
+
+const MAX_METADATA_SIZE: u64 = 64 * 1024 * 1024;
+
+const EBML_HEADER: u32 = 0x1A45_DFA3;
+const EBML_DOC_TYPE: u32 = 0x4282;
+const MKV_SEGMENT: u32 = 0x1853_8067;
+const MKV_INFO: u32 = 0x1549_A966;
+const MKV_TIMECODE_SCALE: u32 = 0x2A_D7B1;
+const MKV_DURATION: u32 = 0x4489;
+const MKV_TRACKS: u32 = 0x1654_AE6B;
+const MKV_TRACK_ENTRY: u32 = 0xAE;
+const MKV_TRACK_TYPE: u32 = 0x83;
+const MKV_TRACK_TYPE_VIDEO: u64 = 1;
+const MKV_TRACK_TYPE_AUDIO: u64 = 2;
+const MKV_CODEC_ID: u32 = 0x86;
+const MKV_VIDEO: u32 = 0xE0;
+const MKV_PIXEL_WIDTH: u32 = 0xB0;
+const MKV_PIXEL_HEIGHT: u32 = 0xBA;
+const MKV_CLUSTER: u32 = 0x1F43_B675;
+
+fn parse_file(path: &Path) -> Result<MediaInfo, ProbeError> {
+    let mut file = File::open(path)?;
+    let file_size = file.metadata()?.len();
+
+    let mut magic = [0u8; 8];
+    if file_size < magic.len() as u64 {
+        return Err(ProbeError::NotVideo("file is too small".into()));
+    }
+    file.read_exact(&mut magic)?;
+    file.seek(SeekFrom::Start(0))?;
+
+    let mut info = if &magic[4..8] == b"ftyp" {
+        parse_mp4(&mut file, file_size)
+    } else if u32::from_be_bytes([magic[0], magic[1], magic[2], magic[3]]) == EBML_HEADER {
+        parse_matroska(&mut file, file_size)
+    } else {
+        return Err(ProbeError::NotVideo("unrecognized container".into()));
+    }
+    .map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof | io::ErrorKind::InvalidData => ProbeError::NotVideo(e.to_string()),
+        _ => ProbeError::Io(e),
+    })??;
+
+    if info.bitrate.is_none()
+        && let Some(duration) = info.duration_seconds.filter(|d| *d > 0.0)
+    {
+        info.bitrate = Some((file_size as f64 * 8.0 / duration) as i64);
+    }
+
+    Ok(info)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+fn read_metadata(file: &mut File, len: u64) -> io::Result<Vec<u8>> {
+    if len > MAX_METADATA_SIZE {
+        return Err(invalid("metadata section too large"));
+    }
+    let mut body = vec![0u8; len as usize];
+    file.read_exact(&mut body)?;
+    Ok(body)
+}
+
+// ---------------------------------------------------------------------------
+// MP4 / ISO BMFF
+// ---------------------------------------------------------------------------
+
+fn parse_mp4(file: &mut File, file_size: u64) -> io::Result<Result<MediaInfo, ProbeError>> {
+    let mut offset = 0;
+    while file_size.saturating_sub(offset) >= 8 {
+        file.seek(SeekFrom::Start(offset))?;
+
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header)?;
+        let box_type = [header[4], header[5], header[6], header[7]];
+        let (header_len, box_size) = match u32::from_be_bytes([header[0], header[1], header[2], header[3]]) {
+            0 => (8, file_size - offset),
+            1 => {
+                let mut large = [0u8; 8];
+                file.read_exact(&mut large)?;
+                (16, u64::from_be_bytes(large))
+            }
+            size => (8, size as u64),
+        };
+        if box_size < header_len {
+            return Err(invalid("invalid box size"));
+        }
+
+        if &box_type == b"moov" {
+            let body = read_metadata(file, box_size - header_len)?;
+            return Ok(parse_moov(&body));
+        }
+        offset = offset.checked_add(box_size).ok_or_else(|| invalid("invalid box size"))?;
+    }
+    Ok(Err(ProbeError::NotVideo("missing moov box".into())))
+}
+
+/// Iterates over the boxes contained in `data`.
+fn boxes(data: &[u8]) -> impl Iterator<Item = ([u8; 4], &[u8])> {
+    let mut offset = 0usize;
+    std::iter::from_fn(move || {
+        let header = data.get(offset..offset + 8)?;
+        let box_type = [header[4], header[5], header[6], header[7]];
+        let (header_len, size) = match u32::from_be_bytes([header[0], header[1], header[2], header[3]]) {
+            0 => (8, data.len() - offset),
+            1 => {
+                let large = data.get(offset + 8..offset + 16)?;
+                (16, u64::from_be_bytes(large.try_into().ok()?) as usize)
+            }
+            size => (8, size as usize),
+        };
+        if size < header_len {
+            return None;
+        }
+        let content = data.get(offset + header_len..offset.checked_add(size)?)?;
+        offset += size;
+        Some((box_type, content))
+    })
+}
+
+fn find_box<'a>(data: &'a [u8], box_type: &[u8; 4]) -> Option<&'a [u8]> {
+    boxes(data).find(|(t, _)| t == box_type).map(|(_, content)| content)
+}
+
+fn be_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn be_u64(data: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_be_bytes(data.get(at..at + 8)?.try_into().ok()?))
+}
+
+fn parse_mvhd(mvhd: &[u8]) -> Option<f64> {
+    let (timescale, duration) = match mvhd.first()? {
+        1 => (be_u32(mvhd, 20)?, be_u64(mvhd, 24)?),
+        _ => (be_u32(mvhd, 12)?, be_u32(mvhd, 16)? as u64),
+    };
+    (timescale > 0).then(|| duration as f64 / timescale as f64)
+}
+
+struct VideoTrack {
+    width: Option<i32>,
+    height: Option<i32>,
+    codec: Option<String>,
+}
+
+/// Handler type of a track, `vide` for video and `soun` for audio.
+fn track_handler(trak: &[u8]) -> Option<&[u8]> {
+    let mdia = find_box(trak, b"mdia")?;
+    find_box(mdia, b"hdlr")?.get(8..12)
+}
+
+fn parse_trak(trak: &[u8]) -> Option<VideoTrack> {
+    if track_handler(trak)? != b"vide" {
+        return None;
+    }
+    let mdia = find_box(trak, b"mdia")?;
+
+    let sample_entry = find_box(mdia, b"minf")
+        .and_then(|minf| find_box(minf, b"stbl"))
+        .and_then(|stbl| find_box(stbl, b"stsd"))
+        .and_then(|stsd| stsd.get(8..));
+    let codec = sample_entry
+        .and_then(|entry| entry.get(4..8))
+        .map(|fourcc| normalize_codec(&String::from_utf8_lossy(fourcc)));
+
+    // tkhd ends with the display width and height as 16.16 fixed point numbers.
+    let tkhd_size = find_box(trak, b"tkhd").and_then(|tkhd| {
+        let n = tkhd.len().checked_sub(8)?;
+        Some(((be_u32(tkhd, n)? >> 16) as i32, (be_u32(tkhd, n + 4)? >> 16) as i32))
+    });
+    let (width, height) = match tkhd_size {
+        Some((w, h)) if w > 0 && h > 0 => (Some(w), Some(h)),
+        _ => (
+            sample_entry.and_then(|entry| be_u16(entry, 32)).map(i32::from),
+            sample_entry.and_then(|entry| be_u16(entry, 34)).map(i32::from),
+        ),
+    };
+
+    Some(VideoTrack { width, height, codec })
+}
+
+fn parse_moov(moov: &[u8]) -> Result<MediaInfo, ProbeError> {
+    let track = boxes(moov)
+        .filter(|(t, _)| t == b"trak")
+        .find_map(|(_, trak)| parse_trak(trak))
+        .ok_or_else(|| ProbeError::NotVideo("no video track".into()))?;
+    let has_audio = boxes(moov)
+        .filter(|(t, _)| t == b"trak")
+        .any(|(_, trak)| track_handler(trak) == Some(b"soun"));
+
+    Ok(MediaInfo {
+        duration_seconds: find_box(moov, b"mvhd").and_then(parse_mvhd),
+        width: track.width,
+        height: track.height,
+        codec: track.codec,
+        bitrate: None,
+        container_format: "mp4".to_string(),
+        has_audio,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// WebM / Matroska
+// ---------------------------------------------------------------------------
+
+fn read_byte<R: Read>(reader: &mut R) -> io::Result<u8> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+fn read_element_id<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let first = read_byte(reader)?;
+    let len = first.leading_zeros() + 1;
+    if len > 4 {
+        return Err(invalid("invalid EBML element id"));
+    }
+    let mut id = first as u32;
+    for _ in 1..len {
+        id = (id << 8) | read_byte(reader)? as u32;
+    }
+    Ok(id)
+}
+
+/// Returns `None` for elements of unknown size.
+fn read_element_size<R: Read>(reader: &mut R) -> io::Result<Option<u64>> {
+    let first = read_byte(reader)?;
+    let len = first.leading_zeros() + 1;
+    if len > 8 {
+        return Err(invalid("invalid EBML element size"));
+    }
+    let mut size = (first as u64) & (0xFF >> len);
+    for _ in 1..len {
+        size = (size << 8) | read_byte(reader)? as u64;
+    }
+    let unknown = (1u64 << (7 * len)) - 1;
+    Ok((size != unknown).then_some(size))
+}
+
+fn ebml_elements(data: &[u8]) -> Vec<(u32, &[u8])> {
+    let mut cursor = Cursor::new(data);
+    let mut elements = Vec::new();
+    while (cursor.position() as usize) < data.len() {
+        let (Ok(id), Ok(Some(size))) = (read_element_id(&mut cursor), read_element_size(&mut cursor)) else {
+            break;
+        };
+        let start = cursor.position() as usize;
+        let end = start.saturating_add(size as usize).min(data.len());
+        elements.push((id, &data[start..end]));
+        cursor.set_position(end as u64);
+    }
+    elements
+}
+
+fn ebml_uint(data: &[u8]) -> u64 {
+    data.iter().fold(0, |acc, byte| (acc << 8) | *byte as u64)
+}
+
+fn ebml_float(data: &[u8]) -> Option<f64> {
+    match data.len() {
+        4 => Some(f32::from_be_bytes(data.try_into().ok()?) as f64),
+        8 => Some(f64::from_be_bytes(data.try_into().ok()?)),
+        _ => None,
+    }
+}
+
+fn ebml_string(data: &[u8]) -> String {
+    String::from_utf8_lossy(data).trim_end_matches('\0').to_string()
+}
+
+fn parse_matroska(file: &mut File, file_size: u64) -> io::Result<Result<MediaInfo, ProbeError>> {
+    if read_element_id(file)? != EBML_HEADER {
+        return Err(invalid("missing EBML header"));
+    }
+    let header_size = read_element_size(file)?.ok_or_else(|| invalid("invalid EBML header size"))?;
+    let header = read_metadata(file, header_size)?;
+    let doc_type = ebml_elements(&header)
+        .into_iter()
+        .find(|(id, _)| *id == EBML_DOC_TYPE)
+        .map_or("matroska".to_string(), |(_, data)| ebml_string(data));
+
+    if read_element_id(file)? != MKV_SEGMENT {
+        return Ok(Err(ProbeError::NotVideo("missing Matroska segment".into())));
+    }
+    let segment_end = match read_element_size(file)? {
+        Some(size) => (file.stream_position()? + size).min(file_size),
+        None => file_size,
+    };
+
+    let mut timecode_scale = 1_000_000u64;
+    let mut duration = None;
+    let mut track = None;
+    let mut has_audio = false;
+    let mut info_seen = false;
+
+    while file.stream_position()? < segment_end && !(info_seen && track.is_some()) {
+        let id = read_element_id(file)?;
+        let Some(size) = read_element_size(file)? else {
+            break;
+        };
+
+        match id {
+            MKV_INFO => {
+                info_seen = true;
+                for (child, data) in ebml_elements(&read_metadata(file, size)?) {
+                    match child {
+                        MKV_TIMECODE_SCALE => timecode_scale = ebml_uint(data),
+                        MKV_DURATION => duration = ebml_float(data),
+                        _ => {}
+                    }
+                }
+            }
+            MKV_TRACKS => {
+                let tracks = read_metadata(file, size)?;
+                let entries: Vec<&[u8]> = ebml_elements(&tracks)
+                    .into_iter()
+                    .filter(|(child, _)| *child == MKV_TRACK_ENTRY)
+                    .map(|(_, entry)| entry)
+                    .collect();
+                track = entries.iter().find_map(|entry| parse_track_entry(entry));
+                has_audio = entries.iter().any(|entry| track_type(entry) == Some(MKV_TRACK_TYPE_AUDIO));
+            }
+            MKV_CLUSTER => break,
+            _ => {
+                file.seek(SeekFrom::Current(size as i64))?;
+            }
+        }
+    }
+
+    let Some(track) = track else {
+        return Ok(Err(ProbeError::NotVideo("no video track".into())));
+    };
+
+    Ok(Ok(MediaInfo {
+        duration_seconds: duration.map(|d| d * timecode_scale as f64 / 1_000_000_000.0),
+        width: track.width,
+        height: track.height,
+        codec: track.codec,
+        bitrate: None,
+        container_format: doc_type,
+        has_audio,
+    }))
+}
+
+fn track_type(entry: &[u8]) -> Option<u64> {
+    ebml_elements(entry)
+        .into_iter()
+        .find(|(id, _)| *id == MKV_TRACK_TYPE)
+        .map(|(_, data)| ebml_uint(data))
+}
+
+fn parse_track_entry(entry: &[u8]) -> Option<VideoTrack> {
+    if track_type(entry)? != MKV_TRACK_TYPE_VIDEO {
+        return None;
+    }
+    let elements = ebml_elements(entry);
+
+    let codec = elements
+        .iter()
+        .find(|(id, _)| *id == MKV_CODEC_ID)
+        .map(|(_, data)| normalize_codec(&ebml_string(data)));
+    let video = elements
+        .iter()
+        .find(|(id, _)| *id == MKV_VIDEO)
+        .map(|(_, data)| ebml_elements(data))
+        .unwrap_or_default();
+    let dimension = |wanted: u32| {
+        video
+            .iter()
+            .find(|(id, _)| *id == wanted)
+            .map(|(_, data)| ebml_uint(data) as i32)
+    };
+
+    Some(VideoTrack {
+        width: dimension(MKV_PIXEL_WIDTH),
+        height: dimension(MKV_PIXEL_HEIGHT),
+        codec,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mp4_box(box_type: &[u8; 4], content: &[u8]) -> Vec<u8> {
+        let mut data = ((content.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(box_type);
+        data.extend_from_slice(content);
+        data
+    }
+
+    fn large_box(box_type: &[u8; 4], size: u64, content: &[u8]) -> Vec<u8> {
+        let mut data = 1u32.to_be_bytes().to_vec();
+        data.extend_from_slice(box_type);
+        data.extend_from_slice(&size.to_be_bytes());
+        data.extend_from_slice(content);
+        data
+    }
+
+    fn trak(handler: &[u8; 4], tkhd_size: (u32, u32), sample_entry: &[u8]) -> Vec<u8> {
+        // tkhd ends with width and height as 16.16 fixed point numbers
+        let mut tkhd = vec![0u8; 76];
+        tkhd.extend_from_slice(&(tkhd_size.0 << 16).to_be_bytes());
+        tkhd.extend_from_slice(&(tkhd_size.1 << 16).to_be_bytes());
+
+        let mut hdlr = vec![0u8; 8];
+        hdlr.extend_from_slice(handler);
+        hdlr.extend_from_slice(&[0u8; 12]);
+
+        let mut stsd = vec![0, 0, 0, 0, 0, 0, 0, 1];
+        stsd.extend_from_slice(sample_entry);
+        let minf = mp4_box(b"minf", &mp4_box(b"stbl", &mp4_box(b"stsd", &stsd)));
+        let mdia = [mp4_box(b"hdlr", &hdlr), minf].concat();
+        mp4_box(b"trak", &[mp4_box(b"tkhd", &tkhd), mp4_box(b"mdia", &mdia)].concat())
+    }
+
+    fn sample_entry(fourcc: &[u8; 4], width: u16, height: u16) -> Vec<u8> {
+        let mut entry = vec![0u8; 86];
+        entry[0..4].copy_from_slice(&86u32.to_be_bytes());
+        entry[4..8].copy_from_slice(fourcc);
+        entry[32..34].copy_from_slice(&width.to_be_bytes());
+        entry[34..36].copy_from_slice(&height.to_be_bytes());
+        entry
+    }
+
+    /// mvhd version 0 with a timescale of 1000 and the given duration.
+    fn mvhd(duration: u32) -> Vec<u8> {
+        let mut mvhd = vec![0u8; 100];
+        mvhd[12..16].copy_from_slice(&1000u32.to_be_bytes());
+        mvhd[16..20].copy_from_slice(&duration.to_be_bytes());
+        mp4_box(b"mvhd", &mvhd)
+    }
+
+    fn ftyp() -> Vec<u8> {
+        mp4_box(b"ftyp", b"isom\0\0\x02\0isomavc1")
+    }
+
+    fn probe_bytes(data: &[u8]) -> Result<MediaInfo, ProbeError> {
+        let path = std::env::temp_dir().join(format!("reels-probe-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, data).unwrap();
+        let result = parse_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        result
+    }
+
+    fn not_video(result: Result<MediaInfo, ProbeError>) -> String {
+        match result {
+            Err(ProbeError::NotVideo(msg)) => msg,
+            other => panic!("expected NotVideo, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_an_mp4_with_video_and_audio() {
+        let moov = [
+            mvhd(12_500),
+            trak(b"vide", (1080, 1920), &sample_entry(b"avc1", 1080, 1920)),
+            trak(b"soun", (0, 0), &sample_entry(b"mp4a", 0, 0)),
+        ]
+        .concat();
+        let data = [ftyp(), mp4_box(b"mdat", &[0u8; 1000]), mp4_box(b"moov", &moov)].concat();
+
+        let info = probe_bytes(&data).unwrap();
+        assert_eq!(info.duration_seconds, Some(12.5));
+        assert_eq!(info.length_seconds(), Some(13));
+        assert_eq!((info.width, info.height), (Some(1080), Some(1920)));
+        assert_eq!(info.codec.as_deref(), Some("h264"));
+        assert_eq!(info.container_format, "mp4");
+        assert!(info.has_audio);
+        assert_eq!(info.bitrate, Some((data.len() as f64 * 8.0 / 12.5) as i64));
+    }
+
+    #[test]
+    fn takes_the_size_from_the_sample_entry_without_a_display_size() {
+        let moov = [mvhd(1_000), trak(b"vide", (0, 0), &sample_entry(b"hvc1", 640, 360))].concat();
+        let info = probe_bytes(&[ftyp(), mp4_box(b"moov", &moov)].concat()).unwrap();
+
+        assert_eq!((info.width, info.height), (Some(640), Some(360)));
+        assert_eq!(info.codec.as_deref(), Some("hevc"));
+        assert!(!info.has_audio);
+    }
+
+    #[test]
+    fn skips_boxes_with_a_64_bit_size() {
+        let moov = [mvhd(2_000), trak(b"vide", (320, 240), &sample_entry(b"avc1", 320, 240))].concat();
+        let mdat = large_box(b"mdat", 16 + 64, &[0u8; 64]);
+        let info = probe_bytes(&[ftyp(), mdat, mp4_box(b"moov", &moov)].concat()).unwrap();
+        assert_eq!(info.duration_seconds, Some(2.0));
+    }
+
+    #[test]
+    fn rejects_a_box_size_that_overflows_the_offset() {
+        let data = [ftyp(), large_box(b"mdat", u64::MAX, &[0u8; 16])].concat();
+        assert_eq!(not_video(probe_bytes(&data)), "invalid box size");
+    }
+
+    #[test]
+    fn rejects_a_box_smaller_than_its_header() {
+        let mut data = ftyp();
+        data.extend_from_slice(&4u32.to_be_bytes());
+        data.extend_from_slice(b"free");
+        assert_eq!(not_video(probe_bytes(&data)), "invalid box size");
+    }
+
+    #[test]
+    fn rejects_an_mp4_without_a_video_track() {
+        assert_eq!(not_video(probe_bytes(&[ftyp(), mp4_box(b"mdat", &[0u8; 16])].concat())), "missing moov box");
+
+        let moov = [mvhd(1_000), trak(b"soun", (0, 0), &sample_entry(b"mp4a", 0, 0))].concat();
+        assert_eq!(not_video(probe_bytes(&[ftyp(), mp4_box(b"moov", &moov)].concat())), "no video track");
+    }
+
+    #[test]
+    fn rejects_unknown_containers() {
+        assert_eq!(not_video(probe_bytes(b"GIF89a\x01\0\x01\0")), "unrecognized container");
+        assert_eq!(not_video(probe_bytes(b"tiny")), "file is too small");
+    }
+
+    fn element(id: u32, content: &[u8]) -> Vec<u8> {
+        let id = id.to_be_bytes();
+        let start = id.iter().position(|byte| *byte != 0).unwrap_or(3);
+        let mut data = id[start..].to_vec();
+        // eight byte size: a marker byte followed by seven bytes of size
+        data.push(0x01);
+        data.extend_from_slice(&(content.len() as u64).to_be_bytes()[1..]);
+        data.extend_from_slice(content);
+        data
+    }
+
+    fn matroska(tracks: &[Vec<u8>]) -> Vec<u8> {
+        let header = element(EBML_HEADER, &element(EBML_DOC_TYPE, b"webm"));
+        let info = element(
+            MKV_INFO,
+            &[element(MKV_TIMECODE_SCALE, &[0x0F, 0x42, 0x40]), element(MKV_DURATION, &4500.0f64.to_be_bytes())].concat(),
+        );
+        let tracks = element(MKV_TRACKS, &tracks.concat());
+        let cluster = element(MKV_CLUSTER, &[0u8; 32]);
+
+        let mut segment = MKV_SEGMENT.to_be_bytes().to_vec();
+        // unknown size, as written by live encoders
+        segment.extend_from_slice(&[0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        [header, segment, info, tracks, cluster].concat()
+    }
+
+    fn track_entry(track_type: u8, codec: &str, size: Option<(u16, u16)>) -> Vec<u8> {
+        let mut entry = [element(MKV_TRACK_TYPE, &[track_type]), element(MKV_CODEC_ID, codec.as_bytes())].concat();
+        if let Some((width, height)) = size {
+            let video = [element(MKV_PIXEL_WIDTH, &width.to_be_bytes()), element(MKV_PIXEL_HEIGHT, &height.to_be_bytes())].concat();
+            entry.extend(element(MKV_VIDEO, &video));
+        }
+        element(MKV_TRACK_ENTRY, &entry)
+    }
+
+    #[test]
+    fn parses_a_webm_with_video_and_audio() {
+        let data = matroska(&[track_entry(2, "A_OPUS", None), track_entry(1, "V_VP9", Some((720, 1280)))]);
+
+        let info = probe_bytes(&data).unwrap();
+        assert_eq!(info.duration_seconds, Some(4.5));
+        assert_eq!((info.width, info.height), (Some(720), Some(1280)));
+        assert_eq!(info.codec.as_deref(), Some("vp9"));
+        assert_eq!(info.container_format, "webm");
+        assert!(info.has_audio);
+    }
+
+    #[test]
+    fn rejects_a_webm_without_a_video_track() {
+        let data = matroska(&[track_entry(2, "A_OPUS", None)]);
+        assert_eq!(not_video(probe_bytes(&data)), "no video track");
+    }
+
+    #[test]
+    fn rejects_a_truncated_ebml_header() {
+        let mut data = element(EBML_HEADER, &element(EBML_DOC_TYPE, b"webm"));
+        data.truncate(10);
+        not_video(probe_bytes(&data));
+    }
+}
@@ -0,0 +1,56 @@
+use std::{path::Path, time::Duration};
+
+use async_trait::async_trait;
+use tokio::process::Command;
+
+use super::{run_with_timeout, FrameExtractor, ProbeError};
+
+/// Extracts and scales frames with an `ffmpeg` subprocess.
+pub struct FfmpegFrameExtractor {
+    binary: String,
+    timeout: Duration,
+}
+
+impl FfmpegFrameExtractor {
+    pub fn new(binary: &str, timeout: Duration) -> Self {
+        FfmpegFrameExtractor {
+            binary: binary.to_string(),
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl FrameExtractor for FfmpegFrameExtractor {
+    async fn extract_frame(
+        &self,
+        source: &Path,
+        offset_seconds: Option<f64>,
+        width: Option<u32>,
+        destination: &Path,
+    ) -> Result<(), ProbeError> {
+        let mut command = Command::new(&self.binary);
+        command.args(["-v", "error", "-y"]);
+        // Seeking before the input keeps extraction fast on long videos.
+        if let Some(offset) = offset_seconds {
+            command.args(["-ss", &format!("{:.3}", offset)]);
+        }
+        command.arg("-i").arg(source).args(["-frames:v", "1"]);
+        if let Some(width) = width {
+            command.args(["-vf", &format!("scale={}:-2", width)]);
+        }
+        command.arg(destination);
+
+        let output = run_with_timeout(command, &self.binary, self.timeout).await?;
+
+        if !output.status.success() {
+            return Err(ProbeError::NotVideo(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+
+        // ffmpeg exits successfully without writing anything when the offset is past the last frame.
+        match tokio::fs::metadata(destination).await {
+            Ok(metadata) if metadata.len() > 0 => Ok(()),
+            _ => Err(ProbeError::NotVideo("no frame at the requested offset".into())),
+        }
+    }
+}
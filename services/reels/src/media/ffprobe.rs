@@ -0,0 +1,107 @@
+use std::{path::Path, time::Duration};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tokio::process::Command;
+
+use super::{normalize_codec, run_with_timeout, MediaInfo, MediaProbe, ProbeError};
+
+/// Probes files with an `ffprobe` subprocess.
+pub struct FfprobeProbe {
+    binary: String,
+    timeout: Duration,
+}
+
+impl FfprobeProbe {
+    pub fn new(binary: &str, timeout: Duration) -> Self {
+        FfprobeProbe {
+            binary: binary.to_string(),
+            timeout,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    format: Option<FfprobeFormat>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeStream {
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    width: Option<i32>,
+    height: Option<i32>,
+    bit_rate: Option<String>,
+    duration: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FfprobeFormat {
+    format_name: Option<String>,
+    duration: Option<String>,
+    bit_rate: Option<String>,
+}
+
+fn normalize_format(format_name: &str) -> String {
+    if format_name.contains("mp4") {
+        "mp4".to_string()
+    } else if format_name.contains("webm") {
+        "webm".to_string()
+    } else {
+        format_name.split(',').next().unwrap_or(format_name).to_string()
+    }
+}
+
+#[async_trait]
+impl MediaProbe for FfprobeProbe {
+    async fn probe(&self, path: &Path) -> Result<MediaInfo, ProbeError> {
+        let mut command = Command::new(&self.binary);
+        command
+            .args(["-v", "error", "-print_format", "json", "-show_format", "-show_streams"])
+            .arg(path);
+        let output = run_with_timeout(command, &self.binary, self.timeout).await?;
+
+        if !output.status.success() {
+            return Err(ProbeError::NotVideo(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+
+        let parsed: FfprobeOutput = serde_json::from_slice(&output.stdout)
+            .map_err(|e| ProbeError::NotVideo(format!("unreadable ffprobe output: {}", e)))?;
+
+        let has_audio = parsed.streams.iter().any(|s| s.codec_type.as_deref() == Some("audio"));
+        let stream = parsed
+            .streams
+            .into_iter()
+            .find(|s| s.codec_type.as_deref() == Some("video"))
+            .ok_or_else(|| ProbeError::NotVideo("no video stream".into()))?;
+        let format = parsed.format;
+
+        let duration_seconds = format
+            .as_ref()
+            .and_then(|f| f.duration.as_deref())
+            .or(stream.duration.as_deref())
+            .and_then(|d| d.parse::<f64>().ok());
+        let bitrate = format
+            .as_ref()
+            .and_then(|f| f.bit_rate.as_deref())
+            .or(stream.bit_rate.as_deref())
+            .and_then(|b| b.parse::<i64>().ok());
+        let container_format = format
+            .as_ref()
+            .and_then(|f| f.format_name.as_deref())
+            .map_or("unknown".to_string(), normalize_format);
+
+        Ok(MediaInfo {
+            duration_seconds,
+            width: stream.width,
+            height: stream.height,
+            codec: stream.codec_name.as_deref().map(normalize_codec),
+            bitrate,
+            container_format,
+            has_audio,
+        })
+    }
+}
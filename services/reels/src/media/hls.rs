@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use tokio::process::Command;
+
+use crate::config::RenditionSettings;
+
+use super::{ProbeError, Transcoder};
+
+pub const MASTER_PLAYLIST: &str = "master.m3u8";
+const RENDITION_PLAYLIST: &str = "index.m3u8";
+
+/// Encodes HLS renditions (H.264/AAC in MPEG-TS segments) with an `ffmpeg` subprocess.
+pub struct FfmpegTranscoder {
+    binary: String,
+    segment_seconds: u32,
+}
+
+impl FfmpegTranscoder {
+    pub fn new(binary: &str, segment_seconds: u32) -> Self {
+        FfmpegTranscoder {
+            binary: binary.to_string(),
+            segment_seconds,
+        }
+    }
+}
+
+#[async_trait]
+impl Transcoder for FfmpegTranscoder {
+    async fn transcode(
+        &self,
+        source: &Path,
+        rendition: &RenditionSettings,
+        output_dir: &Path,
+    ) -> Result<(), ProbeError> {
+        let rendition_dir = output_dir.join(&rendition.name);
+        tokio::fs::create_dir_all(&rendition_dir).await?;
+
+        let video_bitrate = rendition.video_bitrate_kbps;
+        // Keyframes on segment boundaries so every segment starts decodable.
+        let keyframe_expr = format!("expr:gte(t,n_forced*{})", self.segment_seconds);
+        let output = Command::new(&self.binary)
+            .args(["-v", "error", "-y", "-i"])
+            .arg(source)
+            .args(["-map", "0:v:0", "-map", "0:a:0?"])
+            .args(["-vf", &format!("scale=-2:{}", rendition.height)])
+            .args(["-c:v", "libx264", "-preset", "veryfast", "-profile:v", "main", "-pix_fmt", "yuv420p"])
+            .args(["-b:v", &format!("{}k", video_bitrate)])
+            .args(["-maxrate", &format!("{}k", video_bitrate * 107 / 100)])
+            .args(["-bufsize", &format!("{}k", video_bitrate * 3 / 2)])
+            .args(["-force_key_frames", &keyframe_expr])
+            .args(["-c:a", "aac", "-ac", "2", "-b:a", &format!("{}k", rendition.audio_bitrate_kbps)])
+            .args(["-f", "hls", "-hls_time", &self.segment_seconds.to_string()])
+            .args(["-hls_playlist_type", "vod", "-hls_segment_filename"])
+            .arg(rendition_dir.join("segment_%04d.ts"))
+            .arg(rendition_dir.join(RENDITION_PLAYLIST))
+            .output()
+            .await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => ProbeError::Unavailable(format!("{} not found", self.binary)),
+                _ => ProbeError::Io(e),
+            })?;
+
+        if !output.status.success() {
+            return Err(ProbeError::NotVideo(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Renditions worth producing for a source of the given height. Upscaling is pointless,
+/// but the smallest rendition is always kept so every video gets a stream.
+pub fn select_renditions(renditions: &[RenditionSettings], source_height: Option<i32>) -> Vec<RenditionSettings> {
+    let mut selected: Vec<RenditionSettings> = renditions
+        .iter()
+        .filter(|r| source_height.is_none_or(|h| r.height as i32 <= h))
+        .cloned()
+        .collect();
+
+    if selected.is_empty()
+        && let Some(smallest) = renditions.iter().min_by_key(|r| r.height)
+    {
+        selected.push(smallest.clone());
+    }
+    selected.sort_by_key(|r| r.height);
+    selected
+}
+
+/// Master playlist referencing each rendition's playlist, lowest bandwidth first. Players refuse
+/// variants whose CODECS name a stream that is missing, so AAC is only listed for sources with audio.
+pub fn master_playlist(renditions: &[RenditionSettings], source_size: Option<(i32, i32)>, has_audio: bool) -> String {
+    let codecs = if has_audio { "avc1.4d401f,mp4a.40.2" } else { "avc1.4d401f" };
+    let mut playlist = String::from("#EXTM3U\n#EXT-X-VERSION:3\n");
+    for rendition in renditions {
+        let audio_bitrate_kbps = if has_audio { rendition.audio_bitrate_kbps } else { 0 };
+        let bandwidth = (rendition.video_bitrate_kbps + audio_bitrate_kbps) as u64 * 1000;
+        let resolution = source_size
+            .filter(|(_, h)| *h > 0)
+            .map(|(w, h)| {
+                // Matches ffmpeg's `scale=-2:<height>`, which rounds the width to an even number.
+                let width = (w as f64 * rendition.height as f64 / h as f64 / 2.0).round() as u32 * 2;
+                format!(",RESOLUTION={}x{}", width, rendition.height)
+            })
+            .unwrap_or_default();
+        playlist.push_str(&format!(
+            "#EXT-X-STREAM-INF:BANDWIDTH={}{},CODECS=\"{}\"\n{}/{}\n",
+            bandwidth, resolution, codecs, rendition.name, RENDITION_PLAYLIST
+        ));
+    }
+    playlist
+}
+
+pub fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(std::ffi::OsStr::to_str) {
+        Some("m3u8") => "application/vnd.apple.mpegurl",
+        Some("ts") => "video/mp2t",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,209 @@
+use std::{path::Path, process::Output, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use log::warn;
+use thiserror::Error;
+use tokio::process::Command;
+
+use crate::{
+    config::{MediaSettings, RenditionSettings, TranscodeSettings},
+    error::{error::AppError, problem::ErrorCode},
+};
+
+pub mod box_parser;
+pub mod ffmpeg;
+pub mod ffprobe;
+pub mod hls;
+
+/// Technical metadata read from an uploaded file.
+#[derive(Debug, Clone, Default)]
+pub struct MediaInfo {
+    pub duration_seconds: Option<f64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub codec: Option<String>,
+    pub bitrate: Option<i64>,
+    pub container_format: String,
+    pub has_audio: bool,
+}
+
+impl MediaInfo {
+    pub fn length_seconds(&self) -> Option<i32> {
+        self.duration_seconds.map(|d| d.ceil() as i32)
+    }
+}
+
+/// Stored poster and thumbnail images of a video.
+#[derive(Debug, Clone)]
+pub struct Artwork {
+    pub poster_key: String,
+    pub poster_url: String,
+    pub thumbnail_key: String,
+    pub thumbnail_url: String,
+}
+
+impl Artwork {
+    pub fn keys(&self) -> [&str; 2] {
+        [&self.poster_key, &self.thumbnail_key]
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ProbeError {
+    #[error("Not a decodable video: {0}")]
+    NotVideo(String),
+    #[error("Probe unavailable: {0}")]
+    Unavailable(String),
+    #[error("Probe I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl From<ProbeError> for AppError {
+    fn from(e: ProbeError) -> Self {
+        match e {
+            ProbeError::NotVideo(msg) => AppError::BadRequest(ErrorCode::VideoNotDecodable, format!("Uploaded file is not a decodable video: {}", msg)),
+            ProbeError::Unavailable(msg) => AppError::Unavailable(format!("Media probe unavailable: {}", msg)),
+            e => AppError::InternalError(e.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+pub trait MediaProbe: Send + Sync {
+    async fn probe(&self, path: &Path) -> Result<MediaInfo, ProbeError>;
+}
+
+#[async_trait]
+pub trait FrameExtractor: Send + Sync {
+    /// Writes a single frame of `source` to the image file `destination`, whose extension selects the format.
+    /// Without an offset the first frame is used, which also re-encodes still images.
+    async fn extract_frame(
+        &self,
+        source: &Path,
+        offset_seconds: Option<f64>,
+        width: Option<u32>,
+        destination: &Path,
+    ) -> Result<(), ProbeError>;
+}
+
+#[async_trait]
+pub trait Transcoder: Send + Sync {
+    /// Writes the playlist and segments of one rendition into `output_dir/<rendition name>/`.
+    async fn transcode(
+        &self,
+        source: &Path,
+        rendition: &RenditionSettings,
+        output_dir: &Path,
+    ) -> Result<(), ProbeError>;
+}
+
+/// Uses the primary probe and falls back to the secondary one when the primary is not available.
+pub struct FallbackProbe {
+    primary: Box<dyn MediaProbe>,
+    fallback: Box<dyn MediaProbe>,
+}
+
+#[async_trait]
+impl MediaProbe for FallbackProbe {
+    async fn probe(&self, path: &Path) -> Result<MediaInfo, ProbeError> {
+        match self.primary.probe(path).await {
+            Err(ProbeError::Unavailable(reason)) => {
+                warn!("Falling back to the built-in media parser: {}", reason);
+                self.fallback.probe(path).await
+            }
+            result => result,
+        }
+    }
+}
+
+pub fn probe_from_settings(settings: &MediaSettings) -> Arc<dyn MediaProbe> {
+    Arc::new(FallbackProbe {
+        primary: Box::new(ffprobe::FfprobeProbe::new(&settings.ffprobe_path, settings.process_timeout())),
+        fallback: Box::new(box_parser::BoxParserProbe),
+    })
+}
+
+pub fn frames_from_settings(settings: &MediaSettings) -> Arc<dyn FrameExtractor> {
+    Arc::new(ffmpeg::FfmpegFrameExtractor::new(&settings.ffmpeg_path, settings.process_timeout()))
+}
+
+pub fn transcoder_from_settings(media: &MediaSettings, transcoding: &TranscodeSettings) -> Arc<dyn Transcoder> {
+    Arc::new(hls::FfmpegTranscoder::new(&media.ffmpeg_path, transcoding.segment_seconds))
+}
+
+/// Runs `binary` to completion. A process still running after `timeout` is killed and the file
+/// treated as not decodable, as crafted files can keep ffprobe and ffmpeg busy indefinitely.
+pub(crate) async fn run_with_timeout(mut command: Command, binary: &str, timeout: Duration) -> Result<Output, ProbeError> {
+    command.kill_on_drop(true);
+    match tokio::time::timeout(timeout, command.output()).await {
+        Ok(Ok(output)) => Ok(output),
+        Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+            Err(ProbeError::Unavailable(format!("{} not found", binary)))
+        }
+        Ok(Err(e)) => Err(ProbeError::Io(e)),
+        Err(_) => Err(ProbeError::NotVideo(format!(
+            "{} did not finish within {} seconds",
+            binary,
+            timeout.as_secs()
+        ))),
+    }
+}
+
+pub(crate) fn normalize_codec(codec: &str) -> String {
+    match codec {
+        "avc1" | "avc3" | "V_MPEG4/ISO/AVC" => "h264",
+        "hvc1" | "hev1" | "V_MPEGH/ISO/HEVC" => "hevc",
+        "vp08" | "V_VP8" => "vp8",
+        "vp09" | "V_VP9" => "vp9",
+        "av01" | "V_AV1" => "av1",
+        "mp4v" => "mpeg4",
+        other => return other.to_lowercase(),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn falls_back_to_the_box_parser_without_ffprobe() {
+        let probe = FallbackProbe {
+            primary: Box::new(ffprobe::FfprobeProbe::new("/nonexistent/ffprobe", Duration::from_secs(5))),
+            fallback: Box::new(box_parser::BoxParserProbe),
+        };
+        // an EBML header naming the document type, followed by a segment without tracks
+        let data = [
+            &[0x1A, 0x45, 0xDF, 0xA3, 0x87, 0x42, 0x82, 0x84][..],
+            b"webm",
+            &[0x18, 0x53, 0x80, 0x67, 0x80],
+        ]
+        .concat();
+        let path = std::env::temp_dir().join(format!("reels-fallback-{}", uuid::Uuid::new_v4()));
+        tokio::fs::write(&path, data).await.unwrap();
+
+        let result = probe.probe(&path).await;
+        tokio::fs::remove_file(&path).await.unwrap();
+        // only the box parser reads the file itself
+        assert!(matches!(result, Err(ProbeError::NotVideo(msg)) if msg == "no video track"));
+    }
+
+    #[actix_rt::test]
+    async fn kills_processes_that_run_too_long() {
+        let mut command = Command::new("sleep");
+        command.arg("10");
+        let started = std::time::Instant::now();
+
+        let result = run_with_timeout(command, "sleep", Duration::from_millis(100)).await;
+        assert!(matches!(result, Err(ProbeError::NotVideo(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[actix_rt::test]
+    async fn reports_a_missing_binary_as_unavailable() {
+        let result = run_with_timeout(Command::new("/nonexistent/ffmpeg"), "ffmpeg", Duration::from_secs(5)).await;
+        assert!(matches!(result, Err(ProbeError::Unavailable(_))));
+    }
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use uuid::Uuid;
+
+use super::audit_outcome::AuditOutcome;
+
+/// A row of the `audit_log` table, written for refused and moderated requests.
+#[derive(Clone, Debug)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    pub actor_id: Uuid,
+    /// The roles of the caller at the time of the request.
+    pub actor_roles: Vec<String>,
+    /// What was attempted, e.g. `reel.delete`.
+    pub action: String,
+    pub resource_id: Option<Uuid>,
+    pub resource_owner_id: Option<Uuid>,
+    pub outcome: AuditOutcome,
+    pub created_at: DateTime<Utc>,
+}
+
+impl<'c> FromRow<'c, PgRow> for AuditEntry {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(AuditEntry {
+            id: row.try_get("id")?,
+            actor_id: row.try_get("actor_id")?,
+            actor_roles: row.try_get("actor_roles")?,
+            action: row.try_get("action")?,
+            resource_id: row.try_get("resource_id")?,
+            resource_owner_id: row.try_get("resource_owner_id")?,
+            outcome: row
+                .try_get::<String, _>("outcome")?
+                .parse()
+                .map_err(|e: String| sqlx::Error::ColumnDecode {
+                    index: "outcome".into(),
+                    source: e.into(),
+                })?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
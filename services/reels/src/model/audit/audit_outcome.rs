@@ -0,0 +1,37 @@
+use std::{fmt, str::FromStr};
+
+/// What happened to an audited request. Owners acting on their own content are not recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditOutcome {
+    /// The caller neither owns the resource nor holds a role allowing the action.
+    Denied,
+    /// A moderator or admin acted on content posted by someone else.
+    Override,
+}
+
+impl AuditOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOutcome::Denied => "denied",
+            AuditOutcome::Override => "override",
+        }
+    }
+}
+
+impl fmt::Display for AuditOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditOutcome {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "denied" => Ok(AuditOutcome::Denied),
+            "override" => Ok(AuditOutcome::Override),
+            other => Err(format!("Unknown audit outcome: {}", other)),
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod audit_entry;
+pub mod audit_outcome;
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
+pub struct Comment {
+    pub id: Uuid,
+    pub reel_id: Uuid,
+    /// The comment this one replies to. Replies cannot be replied to.
+    pub parent_id: Option<Uuid>,
+    pub author_id: Uuid,
+    /// Empty once the comment is deleted.
+    #[schema(example = "Great recipe!")]
+    pub body: String,
+    /// Replies that are not deleted.
+    pub reply_count: i32,
+    pub created_at: DateTime<Utc>,
+    /// Last time the body was edited.
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Set when the comment was deleted. It is still listed so its replies keep their place.
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl<'c> FromRow<'c, PgRow> for Comment {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Comment {
+            id: row.try_get("id")?,
+            reel_id: row.try_get("reel_id")?,
+            parent_id: row.try_get("parent_id")?,
+            author_id: row.try_get("author_id")?,
+            body: row.try_get("body")?,
+            reply_count: row.try_get("reply_count")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+            deleted_at: row.try_get("deleted_at")?,
+        })
+    }
+}
@@ -0,0 +1,98 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::comment::Comment;
+
+/// Position in a list of comments ordered by `(created_at, id)`, or by `(reply_count, created_at, id)`
+/// for the top comments. Opaque to clients, like `ReelCursor`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CommentCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+    pub reply_count: Option<i32>,
+}
+
+impl CommentCursor {
+    pub fn encode(&self) -> String {
+        let micros = self.created_at.timestamp_micros();
+        let value = match self.reply_count {
+            Some(reply_count) => format!("{}:{}:{}", micros, self.id, reply_count),
+            None => format!("{}:{}", micros, self.id),
+        };
+        URL_SAFE_NO_PAD.encode(value)
+    }
+
+    pub fn decode(token: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(token).ok()?;
+        let value = String::from_utf8(bytes).ok()?;
+        let mut parts = value.split(':');
+        let micros = parts.next()?.parse().ok()?;
+        let id = parts.next()?.parse().ok()?;
+        let reply_count = parts.next().map(|count| count.parse::<i32>()).transpose().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(CommentCursor {
+            created_at: DateTime::from_timestamp_micros(micros)?,
+            id,
+            reply_count,
+        })
+    }
+
+    /// Position after `comment`, carrying its reply count when paging through the top comments.
+    pub fn after(comment: &Comment, with_reply_count: bool) -> Self {
+        CommentCursor {
+            created_at: comment.created_at,
+            id: comment.id,
+            reply_count: with_reply_count.then_some(comment.reply_count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(reply_count: i32) -> Comment {
+        Comment {
+            id: Uuid::new_v4(),
+            reel_id: Uuid::new_v4(),
+            parent_id: None,
+            author_id: Uuid::new_v4(),
+            body: "Tasty".into(),
+            reply_count,
+            created_at: DateTime::from_timestamp_micros(1_792_310_400_123_456).unwrap(),
+            updated_at: None,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn round_trips_newest_and_top_cursors() {
+        let comment = comment(7);
+        for with_reply_count in [false, true] {
+            let cursor = CommentCursor::after(&comment, with_reply_count);
+            assert_eq!(cursor.reply_count, with_reply_count.then_some(7));
+            assert_eq!(CommentCursor::decode(&cursor.encode()), Some(cursor));
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        let id = Uuid::new_v4();
+        for value in [
+            String::new(),
+            "1792310400123456".to_string(),
+            "1792310400123456:not-a-uuid".to_string(),
+            format!("1792310400123456:{}:many", id),
+            format!("1792310400123456:{}:1.5", id),
+            format!("1792310400123456:{}:3:extra", id),
+            format!("{}:{}", i64::MIN, id),
+        ] {
+            assert_eq!(CommentCursor::decode(&URL_SAFE_NO_PAD.encode(&value)), None, "{:?}", value);
+        }
+        assert_eq!(CommentCursor::decode("=="), None);
+    }
+}
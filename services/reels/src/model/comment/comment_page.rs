@@ -0,0 +1,10 @@
+use utoipa::ToSchema;
+
+use super::comment::Comment;
+
+#[derive(serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct CommentPage {
+    pub comments: Vec<Comment>,
+    /// Pass as `cursor` with the same `sort` to get the next page. Missing on the last page.
+    pub next_cursor: Option<String>,
+}
@@ -0,0 +1,37 @@
+use std::{fmt, str::FromStr};
+
+/// Order of the comments on a reel. Replies are always listed oldest first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CommentSort {
+    #[default]
+    Newest,
+    /// Most replied to first.
+    Top,
+}
+
+impl CommentSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommentSort::Newest => "newest",
+            CommentSort::Top => "top",
+        }
+    }
+}
+
+impl fmt::Display for CommentSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CommentSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "newest" => Ok(CommentSort::Newest),
+            "top" => Ok(CommentSort::Top),
+            other => Err(format!("Unknown comment order: {}", other)),
+        }
+    }
+}
@@ -0,0 +1,6 @@
+#[allow(clippy::module_inception)]
+pub mod comment;
+pub mod comment_cursor;
+pub mod comment_page;
+pub mod comment_sort;
+pub mod post_comment;
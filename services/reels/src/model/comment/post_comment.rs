@@ -0,0 +1,19 @@
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, ToSchema, Debug)]
+pub struct PostComment {
+    #[schema(example = "Great recipe!")]
+    pub body: String,
+
+    /// Makes the comment a reply. The parent must be a comment on the same reel that is not a reply itself.
+    #[serde(default)]
+    #[schema(example = "444e8400-e29b-41d4-a716-446655440000")]
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, ToSchema, Debug)]
+pub struct PutComment {
+    #[schema(example = "Great recipe, thanks!")]
+    pub body: String,
+}
@@ -0,0 +1,3 @@
+pub mod reaction_kind;
+pub mod reel_engagement;
+pub mod reel_reaction;
@@ -0,0 +1,31 @@
+/// Something a user can do to a reel at most once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReactionKind {
+    Like,
+    Save,
+}
+
+impl ReactionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReactionKind::Like => "like",
+            ReactionKind::Save => "save",
+        }
+    }
+
+    /// The table holding one row per user and reel.
+    pub fn table(&self) -> &'static str {
+        match self {
+            ReactionKind::Like => "reel_likes",
+            ReactionKind::Save => "reel_saves",
+        }
+    }
+
+    /// The column of `reels` counting the rows of `table`.
+    pub fn counter(&self) -> &'static str {
+        match self {
+            ReactionKind::Like => "like_count",
+            ReactionKind::Save => "save_count",
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The counters of a reel and what the calling user did to it.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
+pub struct ReelEngagement {
+    pub reel_id: Uuid,
+    #[schema(example = 12)]
+    pub like_count: i64,
+    #[schema(example = 3)]
+    pub save_count: i64,
+    pub liked_by_me: bool,
+    pub saved_by_me: bool,
+}
+
+impl<'c> FromRow<'c, PgRow> for ReelEngagement {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(ReelEngagement {
+            reel_id: row.try_get("reel_id")?,
+            like_count: row.try_get("like_count")?,
+            save_count: row.try_get("save_count")?,
+            liked_by_me: row.try_get("liked_by_me")?,
+            saved_by_me: row.try_get("saved_by_me")?,
+        })
+    }
+}
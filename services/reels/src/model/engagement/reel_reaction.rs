@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use uuid::Uuid;
+
+/// A row of `reel_likes` or `reel_saves`.
+#[derive(Clone, Debug)]
+pub struct ReelReaction {
+    pub user_id: Uuid,
+    pub reel_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl<'c> FromRow<'c, PgRow> for ReelReaction {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(ReelReaction {
+            user_id: row.try_get("user_id")?,
+            reel_id: row.try_get("reel_id")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
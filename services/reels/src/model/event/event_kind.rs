@@ -0,0 +1,46 @@
+use std::{fmt, str::FromStr};
+
+use utoipa::ToSchema;
+
+/// What a player reports while a reel is on screen.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EventKind {
+    Play,
+    Progress,
+    /// The video played to the end.
+    Complete,
+    /// The viewer moved on before the end.
+    Skip,
+}
+
+impl EventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Play => "play",
+            EventKind::Progress => "progress",
+            EventKind::Complete => "complete",
+            EventKind::Skip => "skip",
+        }
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "play" => Ok(EventKind::Play),
+            "progress" => Ok(EventKind::Progress),
+            "complete" => Ok(EventKind::Complete),
+            "skip" => Ok(EventKind::Skip),
+            other => Err(format!("Unknown event kind: {}", other)),
+        }
+    }
+}
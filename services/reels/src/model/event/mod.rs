@@ -0,0 +1,4 @@
+pub mod event_kind;
+pub mod post_reel_events;
+pub mod reel_event;
+pub mod reel_view;
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::event_kind::EventKind;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, ToSchema, Debug)]
+pub struct PostReelEvent {
+    pub reel_id: Uuid,
+    pub kind: EventKind,
+    /// Milliseconds of the video watched so far.
+    #[schema(example = 4500)]
+    pub watch_ms: i64,
+    /// When the player saw it happen. Defaults to the time the batch arrives.
+    #[serde(default)]
+    pub occurred_at: Option<DateTime<Utc>>,
+}
+
+/// Events a player collected since its last report.
+#[derive(serde::Serialize, serde::Deserialize, Clone, ToSchema, Debug)]
+pub struct PostReelEvents {
+    /// Generated by the client for each app session. Identifies anonymous viewers.
+    #[schema(example = "7d0c8f2e-5b1a-4c3e-9f6d-2a8b4e1c9d70")]
+    pub session_id: String,
+    pub events: Vec<PostReelEvent>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct EventReceipt {
+    /// Events stored. Events for reels that do not exist are dropped.
+    #[schema(example = 12)]
+    pub accepted: u32,
+}
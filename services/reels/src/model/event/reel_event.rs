@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use uuid::Uuid;
+
+use super::event_kind::EventKind;
+
+/// A row of `reel_events`.
+#[derive(Clone, Debug)]
+pub struct ReelEvent {
+    pub id: i64,
+    pub reel_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub session_id: String,
+    pub kind: EventKind,
+    /// Milliseconds of the video watched so far.
+    pub watch_ms: i64,
+    pub occurred_at: DateTime<Utc>,
+    pub received_at: DateTime<Utc>,
+}
+
+impl ReelEvent {
+    /// Whose views the event counts towards: the user when signed in, the session otherwise.
+    pub fn viewer(&self) -> String {
+        match self.user_id {
+            Some(user_id) => user_id.to_string(),
+            None => format!("session:{}", self.session_id),
+        }
+    }
+}
+
+impl<'c> FromRow<'c, PgRow> for ReelEvent {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(ReelEvent {
+            id: row.try_get("id")?,
+            reel_id: row.try_get("reel_id")?,
+            user_id: row.try_get("user_id")?,
+            session_id: row.try_get("session_id")?,
+            kind: row
+                .try_get::<String, _>("kind")?
+                .parse()
+                .map_err(|e: String| sqlx::Error::ColumnDecode {
+                    index: "kind".into(),
+                    source: e.into(),
+                })?,
+            watch_ms: row.try_get("watch_ms")?,
+            occurred_at: row.try_get("occurred_at")?,
+            received_at: row.try_get("received_at")?,
+        })
+    }
+}
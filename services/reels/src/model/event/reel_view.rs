@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use uuid::Uuid;
+
+/// A row of `reel_views`, one counted view.
+#[derive(Clone, Debug)]
+pub struct ReelView {
+    pub id: Uuid,
+    pub reel_id: Uuid,
+    pub viewer: String,
+    pub user_id: Option<Uuid>,
+    pub started_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub watch_ms: i64,
+    /// Share of the video watched, from 0 to 1.
+    pub completion: f64,
+}
+
+impl<'c> FromRow<'c, PgRow> for ReelView {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(ReelView {
+            id: row.try_get("id")?,
+            reel_id: row.try_get("reel_id")?,
+            viewer: row.try_get("viewer")?,
+            user_id: row.try_get("user_id")?,
+            started_at: row.try_get("started_at")?,
+            last_seen_at: row.try_get("last_seen_at")?,
+            watch_ms: row.try_get("watch_ms")?,
+            completion: row.try_get("completion")?,
+        })
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::job_status::JobStatus;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
+pub struct MediaJob {
+    pub id: Uuid,
+    #[schema(example = "transcode")]
+    pub kind: String,
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    /// Earliest time the next attempt may start.
+    pub run_at: DateTime<Utc>,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl<'c> FromRow<'c, PgRow> for MediaJob {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(MediaJob {
+            id: row.try_get("id")?,
+            kind: row.try_get("kind")?,
+            payload: row.try_get("payload")?,
+            status: row
+                .try_get::<String, _>("status")?
+                .parse()
+                .map_err(|e: String| sqlx::Error::ColumnDecode {
+                    index: "status".into(),
+                    source: e.into(),
+                })?,
+            attempts: row.try_get("attempts")?,
+            max_attempts: row.try_get("max_attempts")?,
+            run_at: row.try_get("run_at")?,
+            locked_at: row.try_get("locked_at")?,
+            last_error: row.try_get("last_error")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
@@ -0,0 +1,32 @@
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Work a background job performs. Stored as the job payload.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MediaTask {
+    /// Encode the HLS renditions of a video.
+    Transcode { video_id: Uuid },
+    /// Generate the poster and thumbnail from a frame of a video.
+    Thumbnail {
+        video_id: Uuid,
+        offset_seconds: Option<f64>,
+    },
+    /// Remove stored objects that are no longer referenced.
+    Cleanup {
+        #[serde(default)]
+        keys: Vec<String>,
+        #[serde(default)]
+        prefixes: Vec<String>,
+    },
+}
+
+impl MediaTask {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MediaTask::Transcode { .. } => "transcode",
+            MediaTask::Thumbnail { .. } => "thumbnail",
+            MediaTask::Cleanup { .. } => "cleanup",
+        }
+    }
+}
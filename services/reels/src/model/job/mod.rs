@@ -0,0 +1,3 @@
+pub mod job_status;
+pub mod media_job;
+pub mod media_task;
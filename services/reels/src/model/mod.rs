@@ -4,13 +4,71 @@ mod video;
 
 pub type Reel = reel::reel::Reel;
 pub type PostReel = reel::post_reel::PostReel;
+pub type ReelCursor = reel::reel_cursor::ReelCursor;
+pub type ReelPage = reel::reel_page::ReelPage;
+pub type ReelSearch = reel::reel_search::ReelSearch;
+pub type ReelSearchHit = reel::reel_search::ReelSearchHit;
+pub type ReelSearchPage = reel::reel_search::ReelSearchPage;
 
 pub type Video = video::video::Video;
 pub type PostVideo = video::post_video::PostVideo;
 pub type VideoForm = video::post_video::VideoForm;
+pub type PosterFrame = video::poster_frame::PosterFrame;
+pub type CoverForm = video::poster_frame::CoverForm;
+pub type ProcessingStatus = video::processing_status::ProcessingStatus;
 
 pub type ReelWithVideosForm = reel_with_videos::reel_with_videos::ReelWithVideosForm;
 pub type ReelWithVideos = reel_with_videos::reel_with_videos::ReelWithVideos;
+pub type ReelWithVideo = reel_with_videos::reel_with_videos::ReelWithVideo;
+pub type ReelFeed = reel_with_videos::reel_with_videos::ReelFeed;
+
+mod job;
+pub type JobStatus = job::job_status::JobStatus;
+pub type MediaJob = job::media_job::MediaJob;
+pub type MediaTask = job::media_task::MediaTask;
+
+mod upload;
+pub type TusUpload = upload::tus_upload::TusUpload;
+
+mod comment;
+pub type Comment = comment::comment::Comment;
+pub type CommentCursor = comment::comment_cursor::CommentCursor;
+pub type CommentPage = comment::comment_page::CommentPage;
+pub type CommentSort = comment::comment_sort::CommentSort;
+pub type PostComment = comment::post_comment::PostComment;
+pub type PutComment = comment::post_comment::PutComment;
+
+mod event;
+pub type EventKind = event::event_kind::EventKind;
+pub type ReelEvent = event::reel_event::ReelEvent;
+pub type ReelView = event::reel_view::ReelView;
+pub type PostReelEvent = event::post_reel_events::PostReelEvent;
+pub type PostReelEvents = event::post_reel_events::PostReelEvents;
+pub type EventReceipt = event::post_reel_events::EventReceipt;
+
+mod stats;
+pub type ReelDailyStats = stats::reel_daily_stats::ReelDailyStats;
+pub type StatsCounts = stats::creator_stats::StatsCounts;
+pub type DailyStats = stats::creator_stats::DailyStats;
+pub type ReelStats = stats::creator_stats::ReelStats;
+pub type CreatorStats = stats::creator_stats::CreatorStats;
+
+mod engagement;
+pub type ReactionKind = engagement::reaction_kind::ReactionKind;
+pub type ReelEngagement = engagement::reel_engagement::ReelEngagement;
+pub type ReelReaction = engagement::reel_reaction::ReelReaction;
+
+mod tag;
+pub type Tag = tag::tag::Tag;
+pub type TagCount = tag::tag_count::TagCount;
+
+mod recipe;
+pub type RecipeVideo = recipe::recipe_video::RecipeVideo;
+pub type RecipeSummary = recipe::recipe_summary::RecipeSummary;
+
+mod audit;
+pub type AuditEntry = audit::audit_entry::AuditEntry;
+pub type AuditOutcome = audit::audit_outcome::AuditOutcome;
 
 mod health;
 pub type HealthResponse = health::health_response::HealthResponse;
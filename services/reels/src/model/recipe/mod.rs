@@ -0,0 +1,2 @@
+pub mod recipe_summary;
+pub mod recipe_video;
@@ -0,0 +1,22 @@
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The fields of a recipe needed to link to it. Recipes themselves are managed by another service.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
+pub struct RecipeSummary {
+    #[schema(example = "333e8400-e29b-41d4-a716-446655440000")]
+    pub id: Uuid,
+    #[schema(example = "Shakshuka")]
+    pub name: Option<String>,
+}
+
+impl<'c> FromRow<'c, PgRow> for RecipeSummary {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(RecipeSummary {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+        })
+    }
+}
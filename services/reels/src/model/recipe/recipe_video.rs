@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use uuid::Uuid;
+
+/// A video shown on a recipe page, a row of `recipe_videos`.
+#[derive(Clone, Debug)]
+pub struct RecipeVideo {
+    pub recipe_id: Uuid,
+    pub video_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl<'c> FromRow<'c, PgRow> for RecipeVideo {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(RecipeVideo {
+            recipe_id: row.try_get("recipe_id")?,
+            video_id: row.try_get("video_id")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
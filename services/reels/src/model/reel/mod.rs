@@ -1,2 +1,6 @@
 pub mod post_reel;
+#[allow(clippy::module_inception)]
 pub mod reel;
+pub mod reel_cursor;
+pub mod reel_page;
+pub mod reel_search;
@@ -8,4 +8,18 @@ pub struct PostReel {
 
     #[schema(example = "This video shows the best moments.")]
     pub description: String,
+
+    #[serde(default)]
+    #[schema(example = "111e8400-e29b-41d4-a716-446655440000")]
+    pub video_id: Option<Uuid>,
+
+    /// Also shows the reel's video on this recipe.
+    #[serde(default)]
+    #[schema(example = "333e8400-e29b-41d4-a716-446655440000")]
+    pub recipe_id: Option<Uuid>,
+
+    /// Tags besides the `#hashtags` found in the description, with or without the `#`.
+    #[serde(default)]
+    #[schema(example = json!(["pasta", "vegan"]))]
+    pub tags: Vec<String>,
 }
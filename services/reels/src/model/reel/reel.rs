@@ -1,7 +1,7 @@
 use chrono::NaiveDateTime;
 use sqlx::postgres::PgRow;
 use sqlx::{FromRow, Row};
-use utoipa::{ToSchema, schema};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, ToSchema)]
@@ -24,17 +24,54 @@ pub struct Reel {
 
     #[schema(example = "2024-05-04T12:34:56")]
     pub creation_timestamp: NaiveDateTime,
+
+    #[serde(default, skip_deserializing)]
+    #[schema(example = 12)]
+    pub like_count: i64,
+
+    #[serde(default, skip_deserializing)]
+    #[schema(example = 3)]
+    pub save_count: i64,
+
+    /// Views, counting a viewer again only after a pause in their events.
+    #[serde(default, skip_deserializing)]
+    #[schema(example = 240)]
+    pub view_count: i64,
+
+    /// Average share of the video watched per view, from 0 to 1. Missing until the reel is viewed.
+    #[serde(default, skip_deserializing)]
+    #[schema(example = 0.62)]
+    pub completion_rate: Option<f64>,
+
+    /// Set when the request carries a bearer token.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub liked_by_me: Option<bool>,
+
+    /// Set when the request carries a bearer token.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub saved_by_me: Option<bool>,
+}
+
+/// Average of the per view completions, whose sum is kept in `reels.completion_sum`.
+pub fn completion_rate(completion_sum: f64, view_count: i64) -> Option<f64> {
+    (view_count > 0).then(|| completion_sum / view_count as f64)
 }
 
 impl<'c> FromRow<'c, PgRow> for Reel {
     fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
         Ok(Reel {
-            id: row.get(0),
-            video_id: row.get(1),
-            posting_user_id: row.get(2),
-            title: row.get(3),
-            description: row.get(4),
-            creation_timestamp: row.get(5),
+            id: row.try_get("id")?,
+            video_id: row.try_get("video_id")?,
+            posting_user_id: row.try_get("posting_user_id")?,
+            title: row.try_get("title")?,
+            description: row.try_get("description")?,
+            creation_timestamp: row.try_get("creation_timestamp")?,
+            like_count: row.try_get("like_count")?,
+            save_count: row.try_get("save_count")?,
+            view_count: row.try_get("view_count")?,
+            completion_rate: completion_rate(row.try_get("completion_sum")?, row.try_get("view_count")?),
+            liked_by_me: None,
+            saved_by_me: None,
         })
     }
 }
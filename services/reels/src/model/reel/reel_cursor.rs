@@ -0,0 +1,110 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, NaiveDateTime};
+use uuid::Uuid;
+
+use super::reel::Reel;
+
+/// Position in a feed ordered by `(creation_timestamp, id)` descending, or by `(rank, creation_timestamp, id)`
+/// for search results. Clients only see it as an opaque token and pass it back to get the next page.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReelCursor {
+    pub creation_timestamp: NaiveDateTime,
+    pub id: Uuid,
+    pub rank: Option<f32>,
+}
+
+impl ReelCursor {
+    pub fn encode(&self) -> String {
+        let micros = self.creation_timestamp.and_utc().timestamp_micros();
+        let value = match self.rank {
+            Some(rank) => format!("{}:{}:{}", micros, self.id, rank),
+            None => format!("{}:{}", micros, self.id),
+        };
+        URL_SAFE_NO_PAD.encode(value)
+    }
+
+    pub fn decode(token: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(token).ok()?;
+        let value = String::from_utf8(bytes).ok()?;
+        let mut parts = value.split(':');
+        let micros = parts.next()?.parse().ok()?;
+        let id = parts.next()?.parse().ok()?;
+        let rank = parts.next().map(|rank| rank.parse::<f32>()).transpose().ok()?;
+        if parts.next().is_some() || rank.is_some_and(|rank| !rank.is_finite()) {
+            return None;
+        }
+
+        Some(ReelCursor {
+            creation_timestamp: DateTime::from_timestamp_micros(micros)?.naive_utc(),
+            id,
+            rank,
+        })
+    }
+}
+
+impl From<&Reel> for ReelCursor {
+    fn from(reel: &Reel) -> Self {
+        ReelCursor {
+            creation_timestamp: reel.creation_timestamp,
+            id: reel.id,
+            rank: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor(rank: Option<f32>) -> ReelCursor {
+        ReelCursor {
+            creation_timestamp: DateTime::from_timestamp_micros(1_792_310_400_123_456).unwrap().naive_utc(),
+            id: Uuid::new_v4(),
+            rank,
+        }
+    }
+
+    fn token(value: &str) -> String {
+        URL_SAFE_NO_PAD.encode(value)
+    }
+
+    #[test]
+    fn round_trips_feed_and_search_cursors() {
+        for rank in [None, Some(0.0), Some(0.607_927_1), Some(1e-30)] {
+            let cursor = cursor(rank);
+            assert_eq!(ReelCursor::decode(&cursor.encode()), Some(cursor));
+        }
+    }
+
+    #[test]
+    fn tokens_are_url_safe() {
+        let token = cursor(Some(0.5)).encode();
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn rejects_ranks_that_are_not_finite() {
+        let id = Uuid::new_v4();
+        for rank in ["NaN", "inf", "-inf", "infinity"] {
+            assert_eq!(ReelCursor::decode(&token(&format!("1792310400123456:{}:{}", id, rank))), None);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        let id = Uuid::new_v4();
+        for value in [
+            String::new(),
+            "1792310400123456".to_string(),
+            format!("soon:{}", id),
+            "1792310400123456:not-a-uuid".to_string(),
+            format!("1792310400123456:{}:high", id),
+            format!("1792310400123456:{}:0.5:extra", id),
+            format!("{}:{}", i64::MAX, id),
+        ] {
+            assert_eq!(ReelCursor::decode(&token(&value)), None, "{:?}", value);
+        }
+        assert_eq!(ReelCursor::decode("not base64!"), None);
+        assert_eq!(ReelCursor::decode(&URL_SAFE_NO_PAD.encode([0xFF, 0xFE])), None);
+    }
+}
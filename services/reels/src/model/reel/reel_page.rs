@@ -0,0 +1,12 @@
+use utoipa::ToSchema;
+
+use super::reel::Reel;
+
+/// One page of a reel feed, newest first.
+#[derive(serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct ReelPage {
+    pub reels: Vec<Reel>,
+    /// Pass as `cursor` to get the next page. Missing on the last page.
+    #[schema(example = "MTcxNDgyNjA5NjAwMDAwMDo1NTBlODQwMC1lMjliLTQxZDQtYTcxNi00NDY2NTU0NDAwMDA")]
+    pub next_cursor: Option<String>,
+}
@@ -0,0 +1,85 @@
+use chrono::NaiveDateTime;
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::reel::Reel;
+
+/// Filters of a reel search. `query` is the text as typed, matched as word prefixes.
+#[derive(Clone, Debug)]
+pub struct ReelSearch {
+    pub query: String,
+    pub posting_user_id: Option<Uuid>,
+    /// Only reels created at or after this time.
+    pub from: Option<NaiveDateTime>,
+    /// Only reels created before this time.
+    pub to: Option<NaiveDateTime>,
+}
+
+/// Escapes the markup in a headline and turns its markers into `<mark>` tags.
+fn highlight_html(headline: &str) -> String {
+    let mut html = String::with_capacity(headline.len());
+    for c in headline.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            ReelSearchHit::HIGHLIGHT_START => html.push_str("<mark>"),
+            ReelSearchHit::HIGHLIGHT_STOP => html.push_str("</mark>"),
+            c => html.push(c),
+        }
+    }
+    html
+}
+
+/// A reel matching a search, with the matched words wrapped in `<mark>` tags.
+/// Other markup in the title and description is escaped.
+#[derive(serde::Serialize, serde::Deserialize, Clone, ToSchema)]
+pub struct ReelSearchHit {
+    pub reel: Reel,
+    /// Higher is more relevant. Title matches weigh more than description matches.
+    #[schema(example = 0.6079271)]
+    pub rank: f32,
+    #[schema(example = "Funny <mark>Cat</mark> Compilation")]
+    pub title_highlight: String,
+    #[schema(example = "A compilation of the funniest <mark>cat</mark> videos.")]
+    pub description_highlight: String,
+}
+
+impl ReelSearchHit {
+    /// Markers `ts_headline` puts around matched words. They are private-use characters stripped
+    /// from the text beforehand, so they cannot come from the reel itself.
+    pub(crate) const HIGHLIGHT_START: char = '\u{E000}';
+    pub(crate) const HIGHLIGHT_STOP: char = '\u{E001}';
+}
+
+impl<'c> FromRow<'c, PgRow> for ReelSearchHit {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(ReelSearchHit {
+            reel: Reel::from_row(row)?,
+            rank: row.try_get("rank")?,
+            title_highlight: highlight_html(row.try_get("title_highlight")?),
+            description_highlight: highlight_html(row.try_get("description_highlight")?),
+        })
+    }
+}
+
+/// One page of search results, most relevant first.
+#[derive(serde::Serialize, serde::Deserialize, ToSchema)]
+pub struct ReelSearchPage {
+    pub results: Vec<ReelSearchHit>,
+    /// Pass as `cursor` with the same filters to get the next page. Missing on the last page.
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_html_escapes_text_but_not_markers() {
+        let headline = format!("<b>Fish</b> & {}chips{}", ReelSearchHit::HIGHLIGHT_START, ReelSearchHit::HIGHLIGHT_STOP);
+        assert_eq!(highlight_html(&headline), "&lt;b&gt;Fish&lt;/b&gt; &amp; <mark>chips</mark>");
+    }
+}
@@ -1,7 +1,10 @@
 use actix_multipart::form::{MultipartForm, json::Json as MpJson, tempfile::TempFile};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
 use utoipa::*;
 
 use crate::model::{PostReel, PostVideo, Reel, Video};
+use crate::model::reel::reel::completion_rate;
 
 #[derive(Debug, MultipartForm, ToSchema)]
 pub struct ReelWithVideosForm {
@@ -12,10 +15,53 @@ pub struct ReelWithVideosForm {
     pub reel: MpJson<PostReel>,
     #[schema(value_type = PostVideo)]
     pub video: MpJson<PostVideo>,
+    #[schema(value_type = Option<String>, format = Binary)]
+    pub cover: Option<TempFile>,
+}
+
+/// A reel together with its video.
+#[derive(serde::Deserialize, serde::Serialize, ToSchema)]
+pub struct ReelWithVideo {
+    pub reel: Reel,
+    pub video: Video,
+}
+
+/// Reads a `videos` row whose joined reel columns are prefixed with `reel_`.
+impl<'c> FromRow<'c, PgRow> for ReelWithVideo {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(ReelWithVideo {
+            reel: Reel {
+                id: row.try_get("reel_id")?,
+                video_id: row.try_get("reel_video_id")?,
+                posting_user_id: row.try_get("reel_posting_user_id")?,
+                title: row.try_get("reel_title")?,
+                description: row.try_get("reel_description")?,
+                creation_timestamp: row.try_get("reel_creation_timestamp")?,
+                like_count: row.try_get("reel_like_count")?,
+                save_count: row.try_get("reel_save_count")?,
+                view_count: row.try_get("reel_view_count")?,
+                completion_rate: completion_rate(row.try_get("reel_completion_sum")?, row.try_get("reel_view_count")?),
+                liked_by_me: None,
+                saved_by_me: None,
+            },
+            video: Video::from_row(row)?,
+        })
+    }
+}
+
+/// One page of the feed, newest first.
+#[derive(serde::Deserialize, serde::Serialize, ToSchema)]
+pub struct ReelFeed {
+    pub items: Vec<ReelWithVideo>,
+    pub has_more: bool,
+    /// Pass as `cursor` to get the next page. Missing on the last page.
+    pub next_cursor: Option<String>,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, ToSchema)]
 pub struct ReelWithVideos {
     pub reels: Vec<Reel>,
     pub videos: Vec<Video>,
+    /// Pass as `cursor` to get the next page. Missing on the last page and when paging by number.
+    pub next_cursor: Option<String>,
 }
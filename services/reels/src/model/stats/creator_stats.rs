@@ -0,0 +1,57 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// What happened to one or more reels over a period.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, ToSchema)]
+pub struct StatsCounts {
+    #[schema(example = 240)]
+    pub views: i64,
+    #[schema(example = 180)]
+    pub unique_viewers: i64,
+    #[schema(example = 31)]
+    pub likes: i64,
+    #[schema(example = 9)]
+    pub saves: i64,
+    #[schema(example = 4)]
+    pub comments: i64,
+    /// Average share of the video watched per view, from 0 to 100. Missing without views.
+    #[schema(example = 62.5)]
+    pub avg_watch_percentage: Option<f64>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
+pub struct DailyStats {
+    /// UTC day.
+    #[schema(example = "2026-10-18")]
+    pub day: NaiveDate,
+    #[serde(flatten)]
+    pub counts: StatsCounts,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
+pub struct ReelStats {
+    pub reel_id: Uuid,
+    #[schema(example = "Amazing New Video")]
+    pub title: String,
+    pub totals: StatsCounts,
+    /// Every day of the range, oldest first.
+    pub daily: Vec<DailyStats>,
+}
+
+/// Performance of the reels of a user over a range of days.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
+pub struct CreatorStats {
+    #[schema(example = "2026-09-19")]
+    pub from: NaiveDate,
+    #[schema(example = "2026-10-18")]
+    pub to: NaiveDate,
+    /// All reels together. Viewers of several reels count once.
+    pub totals: StatsCounts,
+    /// All reels together, every day of the range, oldest first.
+    pub daily: Vec<DailyStats>,
+    /// Every reel of the user, newest first.
+    pub reels: Vec<ReelStats>,
+    /// When the rollups were last rebuilt. Newer activity is not counted yet.
+    pub refreshed_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,2 @@
+pub mod creator_stats;
+pub mod reel_daily_stats;
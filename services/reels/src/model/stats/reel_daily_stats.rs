@@ -0,0 +1,34 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use uuid::Uuid;
+
+/// A row of `reel_daily_stats`. Likes, saves and comments are the ones made that day and not undone since.
+#[derive(Clone, Debug)]
+pub struct ReelDailyStats {
+    pub reel_id: Uuid,
+    pub day: NaiveDate,
+    pub views: i64,
+    pub unique_viewers: i64,
+    pub completion_sum: f64,
+    pub likes: i64,
+    pub saves: i64,
+    pub comments: i64,
+    pub refreshed_at: DateTime<Utc>,
+}
+
+impl<'c> FromRow<'c, PgRow> for ReelDailyStats {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(ReelDailyStats {
+            reel_id: row.try_get("reel_id")?,
+            day: row.try_get("day")?,
+            views: row.try_get("views")?,
+            unique_viewers: row.try_get("unique_viewers")?,
+            completion_sum: row.try_get("completion_sum")?,
+            likes: row.try_get("likes")?,
+            saves: row.try_get("saves")?,
+            comments: row.try_get("comments")?,
+            refreshed_at: row.try_get("refreshed_at")?,
+        })
+    }
+}
@@ -0,0 +1,3 @@
+#[allow(clippy::module_inception)]
+pub mod tag;
+pub mod tag_count;
@@ -0,0 +1,22 @@
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use uuid::Uuid;
+
+/// A normalized tag: lowercase, without the leading `#`.
+#[derive(Clone, Debug)]
+pub struct Tag {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl<'c> FromRow<'c, PgRow> for Tag {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Tag {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
@@ -0,0 +1,21 @@
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use utoipa::ToSchema;
+
+/// A tag with the number of reels carrying it.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
+pub struct TagCount {
+    #[schema(example = "pasta")]
+    pub name: String,
+    #[schema(example = 42)]
+    pub reel_count: i64,
+}
+
+impl<'c> FromRow<'c, PgRow> for TagCount {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(TagCount {
+            name: row.try_get("name")?,
+            reel_count: row.try_get("reel_count")?,
+        })
+    }
+}
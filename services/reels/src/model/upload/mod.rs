@@ -0,0 +1 @@
+pub mod tus_upload;
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+use uuid::Uuid;
+
+/// A resumable upload created through the tus protocol.
+#[derive(Clone, Debug)]
+pub struct TusUpload {
+    pub id: Uuid,
+    pub posting_user_id: Uuid,
+    pub upload_length: i64,
+    /// Number of bytes received and written so far.
+    pub upload_offset: i64,
+    /// The Upload-Metadata header as sent when the upload was created.
+    pub metadata: String,
+    pub file_name: String,
+    pub content_type: Option<String>,
+    /// The `PostVideo` the video is created with once the upload completes.
+    pub video_metadata: serde_json::Value,
+    pub video_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl TusUpload {
+    pub fn is_complete(&self) -> bool {
+        self.upload_offset >= self.upload_length
+    }
+}
+
+impl<'c> FromRow<'c, PgRow> for TusUpload {
+    fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
+        Ok(TusUpload {
+            id: row.try_get("id")?,
+            posting_user_id: row.try_get("posting_user_id")?,
+            upload_length: row.try_get("upload_length")?,
+            upload_offset: row.try_get("upload_offset")?,
+            metadata: row.try_get("metadata")?,
+            file_name: row.try_get("file_name")?,
+            content_type: row.try_get("content_type")?,
+            video_metadata: row.try_get("video_metadata")?,
+            video_id: row.try_get("video_id")?,
+            created_at: row.try_get("created_at")?,
+            expires_at: row.try_get("expires_at")?,
+        })
+    }
+}
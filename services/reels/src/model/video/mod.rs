@@ -1,2 +1,5 @@
 pub mod post_video;
+pub mod poster_frame;
+pub mod processing_status;
+#[allow(clippy::module_inception)]
 pub mod video;
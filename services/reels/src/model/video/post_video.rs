@@ -1,6 +1,5 @@
 use actix_multipart::form::{json::Json as MpJson, tempfile::TempFile, MultipartForm};
 use utoipa::*;
-use uuid::Uuid;
 
 #[derive(Debug, MultipartForm, ToSchema)]
 pub struct VideoForm {
@@ -9,11 +8,18 @@ pub struct VideoForm {
     pub file: TempFile,
     #[schema(value_type = PostVideo)]
     pub video: MpJson<PostVideo>,
+    #[schema(value_type = Option<String>, format = Binary)]
+    pub cover: Option<TempFile>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
 pub struct PostVideo {
     pub title: String,
     pub description: String,
-    pub video_length_seconds: i32,
+    /// Ignored for uploads, where the length is read from the file itself.
+    #[serde(default)]
+    pub video_length_seconds: Option<i32>,
+    /// Timestamp of the frame used as the poster. Ignored when a cover image is uploaded.
+    #[serde(default)]
+    pub poster_offset_seconds: Option<f64>,
 }
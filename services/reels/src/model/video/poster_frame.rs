@@ -0,0 +1,14 @@
+use actix_multipart::form::{tempfile::TempFile, MultipartForm};
+use utoipa::ToSchema;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
+pub struct PosterFrame {
+    #[schema(example = 2.5)]
+    pub offset_seconds: f64,
+}
+
+#[derive(Debug, MultipartForm, ToSchema)]
+pub struct CoverForm {
+    #[schema(value_type = String, format = Binary)]
+    pub file: TempFile,
+}
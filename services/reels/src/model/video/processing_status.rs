@@ -0,0 +1,44 @@
+use std::{fmt, str::FromStr};
+
+use utoipa::ToSchema;
+
+/// Progress of the HLS renditions of a video.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessingStatus {
+    Pending,
+    Processing,
+    Ready,
+    Failed,
+}
+
+impl ProcessingStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProcessingStatus::Pending => "pending",
+            ProcessingStatus::Processing => "processing",
+            ProcessingStatus::Ready => "ready",
+            ProcessingStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for ProcessingStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProcessingStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ProcessingStatus::Pending),
+            "processing" => Ok(ProcessingStatus::Processing),
+            "ready" => Ok(ProcessingStatus::Ready),
+            "failed" => Ok(ProcessingStatus::Failed),
+            other => Err(format!("Unknown processing status: {}", other)),
+        }
+    }
+}
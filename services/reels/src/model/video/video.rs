@@ -3,6 +3,8 @@ use sqlx::{FromRow, Row};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use super::processing_status::ProcessingStatus;
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, ToSchema)]
 pub struct Video {
     pub id: Uuid,
@@ -11,17 +13,63 @@ pub struct Video {
     pub description: String,
     pub video_length_seconds: i32,
     pub video_url: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub codec: Option<String>,
+    pub bitrate: Option<i64>,
+    pub container_format: Option<String>,
+    pub poster_url: Option<String>,
+    pub thumbnail_url: Option<String>,
+    pub processing_status: ProcessingStatus,
+    /// HLS master playlist, set once the renditions are ready.
+    pub stream_url: Option<String>,
+    #[serde(skip)]
+    pub storage_key: Option<String>,
+    #[serde(skip)]
+    pub poster_key: Option<String>,
+    #[serde(skip)]
+    pub thumbnail_key: Option<String>,
+    #[serde(skip)]
+    pub hls_prefix: Option<String>,
+}
+
+impl Video {
+    /// Keys of every stored object belonging to this video.
+    pub fn storage_keys(&self) -> impl Iterator<Item = &str> {
+        [&self.storage_key, &self.poster_key, &self.thumbnail_key]
+            .into_iter()
+            .filter_map(|key| key.as_deref())
+    }
 }
 
 impl<'c> FromRow<'c, PgRow> for Video {
     fn from_row(row: &PgRow) -> Result<Self, sqlx::Error> {
         Ok(Video {
-            id: row.get(0),
-            posting_user_id: row.get(1),
-            title: row.get(2),
-            description: row.get(3),
-            video_length_seconds: row.get(4),
-            video_url: row.get(5),
+            id: row.try_get("id")?,
+            posting_user_id: row.try_get("posting_user_id")?,
+            title: row.try_get("title")?,
+            description: row.try_get("description")?,
+            video_length_seconds: row.try_get("video_length_seconds")?,
+            video_url: row.try_get("video_url")?,
+            width: row.try_get("width")?,
+            height: row.try_get("height")?,
+            codec: row.try_get("codec")?,
+            bitrate: row.try_get("bitrate")?,
+            container_format: row.try_get("container_format")?,
+            poster_url: row.try_get("poster_url")?,
+            thumbnail_url: row.try_get("thumbnail_url")?,
+            processing_status: row
+                .try_get::<String, _>("processing_status")?
+                .parse()
+                .map_err(|e: String| sqlx::Error::ColumnDecode {
+                    index: "processing_status".into(),
+                    source: e.into(),
+                })?,
+            stream_url: row.try_get("stream_url")?,
+            storage_key: row.try_get("storage_key")?,
+            poster_key: row.try_get("poster_key")?,
+            thumbnail_key: row.try_get("thumbnail_key")?,
+            hls_prefix: row.try_get("hls_prefix")?,
         })
     }
 }
@@ -1,26 +1,81 @@
-use utoipa::OpenApi;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
 
 use crate::controller;
+use crate::error::problem::{ErrorCode, Problem};
 use crate::model::{
-    HealthResponse, PostReel, PostVideo, Reel, ReelWithVideos, ReelWithVideosForm, Video, VideoForm,
+    Comment, CommentPage, CoverForm, CreatorStats, DailyStats, EventKind, EventReceipt, HealthResponse, JobStatus, MediaJob, MediaTask, PostReel, PostVideo, PostComment, PostReelEvent, PostReelEvents, PosterFrame, PutComment, RecipeSummary, Reel, ReelStats, ReelEngagement, ReelFeed, ReelPage, ReelSearchHit, ReelSearchPage, ReelWithVideo, ReelWithVideos, ReelWithVideosForm, StatsCounts, TagCount, Video,
+    VideoForm,
 };
 
+/// Declares the Keycloak access token the `bearer_auth` requirements refer to.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
+    modifiers(&SecurityAddon),
     paths(
         controller::health_controller::health_check,
+        controller::reel_controller::search_reels,
         controller::reel_controller::get_reel_by_id,
         controller::reel_controller::get_reels_paginated,
         controller::reel_controller::get_reels_with_videos_paginated,
+        controller::reel_controller::get_feed,
         controller::reel_controller::get_reels_by_user_id,
         controller::reel_controller::post_reel,
         controller::reel_controller::post_reel_with_video,
+        controller::reel_controller::put_reel,
         controller::reel_controller::delete_reel_with_video,
         controller::video_controller::get_video_by_id,
         controller::video_controller::get_video_by_reel_id,
         controller::video_controller::post_video,
         controller::video_controller::put_video,
+        controller::video_controller::put_video_file,
+        controller::video_controller::put_poster_frame,
+        controller::video_controller::put_cover,
         controller::video_controller::delete_video,
+        controller::video_controller::get_recipes_by_video_id,
+        controller::recipe_controller::get_videos_by_recipe_id,
+        controller::recipe_controller::put_recipe_video,
+        controller::recipe_controller::delete_recipe_video,
+        controller::engagement_controller::like_reel,
+        controller::engagement_controller::unlike_reel,
+        controller::engagement_controller::save_reel,
+        controller::engagement_controller::unsave_reel,
+        controller::engagement_controller::get_saved_reels,
+        controller::comment_controller::get_comments,
+        controller::comment_controller::post_comment,
+        controller::comment_controller::get_comment_by_id,
+        controller::comment_controller::get_replies,
+        controller::comment_controller::put_comment,
+        controller::comment_controller::delete_comment,
+        controller::event_controller::post_events,
+        controller::stats_controller::get_creator_stats,
+        controller::tag_controller::autocomplete_tags,
+        controller::tag_controller::get_trending_tags,
+        controller::tag_controller::get_reels_by_tag,
+        controller::upload_controller::get_upload_options,
+        controller::upload_controller::post_upload,
+        controller::upload_controller::get_upload_offset,
+        controller::upload_controller::patch_upload,
+        controller::upload_controller::delete_upload,
+        controller::admin_controller::get_jobs_paginated,
+        controller::admin_controller::retry_job,
     ),
     components(schemas(
         HealthResponse,
@@ -29,8 +84,35 @@ use crate::model::{
         Video,
         PostVideo,
         VideoForm,
+        PosterFrame,
+        CoverForm,
+        ReelPage,
+        ReelEngagement,
+        Comment,
+        PostComment,
+        PutComment,
+        CommentPage,
+        PostReelEvents,
+        PostReelEvent,
+        EventKind,
+        EventReceipt,
+        CreatorStats,
+        ReelStats,
+        DailyStats,
+        StatsCounts,
+        RecipeSummary,
+        ReelFeed,
+        ReelSearchHit,
+        ReelSearchPage,
+        ReelWithVideo,
         ReelWithVideos,
-        ReelWithVideosForm
+        ReelWithVideosForm,
+        TagCount,
+        MediaJob,
+        MediaTask,
+        JobStatus,
+        Problem,
+        ErrorCode
     ))
 )]
 pub struct ApiDoc;
@@ -0,0 +1,73 @@
+use chrono::Utc;
+use log::{error, warn};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser, dao::database_context::{Database, Table}, model::{AuditEntry, AuditOutcome}
+};
+
+/// Decides whether a user may act on content and records refused and moderated requests in `audit_log`.
+#[derive(Clone)]
+pub struct AuditLog<'a> {
+    entries: Arc<Table<'a, AuditEntry>>,
+}
+
+impl<'a> AuditLog<'a> {
+    pub fn new(db: &Database<'a>) -> Self {
+        AuditLog { entries: db.audit.clone() }
+    }
+
+    /// Allows the owner of the resource and moderators. Returns `false` when the request must be refused.
+    pub async fn authorize_owner(
+        &self,
+        user: &AuthenticatedUser,
+        action: &str,
+        resource_id: Uuid,
+        owner_id: Uuid,
+    ) -> bool {
+        if user.id == owner_id {
+            return true;
+        }
+        let outcome = if user.is_moderator() { AuditOutcome::Override } else { AuditOutcome::Denied };
+        self.record(user, action, Some(resource_id), Some(owner_id), outcome).await;
+        outcome == AuditOutcome::Override
+    }
+
+    /// Allows admins only. Returns `false` when the request must be refused.
+    pub async fn authorize_admin(&self, user: &AuthenticatedUser, action: &str, resource_id: Option<Uuid>) -> bool {
+        if user.is_admin() {
+            return true;
+        }
+        self.record(user, action, resource_id, None, AuditOutcome::Denied).await;
+        false
+    }
+
+    /// Writes an entry. A failure is logged rather than failing the request it describes.
+    pub async fn record(
+        &self,
+        user: &AuthenticatedUser,
+        action: &str,
+        resource_id: Option<Uuid>,
+        resource_owner_id: Option<Uuid>,
+        outcome: AuditOutcome,
+    ) {
+        if outcome == AuditOutcome::Denied {
+            warn!("User {} was refused {} on {:?}", user.id, action, resource_id);
+        }
+
+        let entry = AuditEntry {
+            id: Uuid::new_v4(),
+            actor_id: user.id,
+            actor_roles: user.roles.clone(),
+            action: action.to_string(),
+            resource_id,
+            resource_owner_id,
+            outcome,
+            created_at: Utc::now(),
+        };
+        if let Err(e) = self.entries.post_entry(&entry).await {
+            error!("Failed to record {} of {} by user {}: {}", outcome, action, user.id, e);
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser, dao::database_context::Database, error::{error::AppError, problem::ErrorCode}, model::{AuditOutcome, Comment, CommentCursor, CommentPage, CommentSort, PostComment, PutComment}, service::{audit_service::AuditLog, reel_service::page_size}
+};
+
+/// Longest comment body kept, in characters.
+pub const MAX_COMMENT_LENGTH: usize = 2000;
+
+/// Trims the body and checks it is neither empty nor too long.
+fn comment_body(body: &str) -> Result<String, AppError> {
+    let body = body.trim();
+    if body.is_empty() || body.chars().count() > MAX_COMMENT_LENGTH {
+        return Err(AppError::BadRequest(ErrorCode::InvalidComment, format!(
+            "A comment must have between 1 and {} characters",
+            MAX_COMMENT_LENGTH
+        )));
+    }
+    Ok(body.to_string())
+}
+
+/// A cursor must come from a page with the same order, as only top comment cursors carry a reply count.
+fn parse_comment_cursor(cursor: Option<String>, sort: CommentSort) -> Result<Option<CommentCursor>, AppError> {
+    cursor
+        .map(|token| {
+            CommentCursor::decode(&token)
+                .filter(|cursor| cursor.reply_count.is_some() == (sort == CommentSort::Top))
+                .ok_or_else(|| AppError::BadRequest(ErrorCode::InvalidCursor, "Invalid pagination cursor".into()))
+        })
+        .transpose()
+}
+
+fn into_comment_page(mut comments: Vec<Comment>, limit: u32, sort: CommentSort) -> CommentPage {
+    let next_cursor = if comments.len() > limit as usize {
+        comments.truncate(limit as usize);
+        comments
+            .last()
+            .map(|comment| CommentCursor::after(comment, sort == CommentSort::Top).encode())
+    } else {
+        None
+    };
+    CommentPage { comments, next_cursor }
+}
+
+fn comment_not_found() -> AppError {
+    AppError::NotFound(ErrorCode::CommentNotFound, "Comment not found".into())
+}
+
+#[async_trait]
+pub trait CommentRepository<'a> {
+    fn new(db: Arc<Database<'a>>) -> Self;
+    async fn get_comment_by_id(&self, comment_id: Uuid) -> Result<Comment, AppError>;
+    async fn get_comments(
+        &self,
+        reel_id: Uuid,
+        sort: CommentSort,
+        cursor: Option<String>,
+        limit: u32,
+    ) -> Result<CommentPage, AppError>;
+    async fn get_replies(&self, comment_id: Uuid, cursor: Option<String>, limit: u32) -> Result<CommentPage, AppError>;
+    async fn post_comment(&self, reel_id: Uuid, comment: PostComment, user: &AuthenticatedUser) -> Result<Comment, AppError>;
+    async fn put_comment(&self, comment_id: Uuid, comment: PutComment, user: &AuthenticatedUser) -> Result<Comment, AppError>;
+    async fn delete_comment(&self, comment_id: Uuid, user: &AuthenticatedUser) -> Result<(), AppError>;
+}
+
+pub struct CommentService<'a> {
+    pub db: Arc<Database<'a>>,
+    audit: AuditLog<'a>,
+}
+
+impl CommentService<'_> {
+    async fn check_reel(&self, reel_id: Uuid) -> Result<(), AppError> {
+        match self.db.reels.get_reel_by_id(reel_id).await {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::RowNotFound) => Err(AppError::NotFound(ErrorCode::ReelNotFound, "Reel not found".into())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The parent named in a new comment must be a live top level comment on the same reel.
+    async fn check_parent(&self, reel_id: Uuid, parent_id: Uuid) -> Result<(), AppError> {
+        let parent = match self.db.comments.get_comment_by_id(parent_id).await {
+            Ok(parent) => parent,
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(AppError::BadRequest(ErrorCode::InvalidComment, "Parent comment not found".into()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        if parent.reel_id != reel_id {
+            return Err(AppError::BadRequest(ErrorCode::InvalidComment, "Parent comment is on another reel".into()));
+        }
+        if parent.parent_id.is_some() {
+            return Err(AppError::BadRequest(ErrorCode::InvalidComment, "Replies cannot be replied to".into()));
+        }
+        if parent.deleted_at.is_some() {
+            return Err(AppError::BadRequest(ErrorCode::InvalidComment, "Parent comment was deleted".into()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<'a> CommentRepository<'a> for CommentService<'a> {
+    fn new(db: Arc<Database<'a>>) -> Self {
+        let audit = AuditLog::new(&db);
+        CommentService { db, audit }
+    }
+
+    async fn get_comment_by_id(&self, comment_id: Uuid) -> Result<Comment, AppError> {
+        match self.db.comments.get_comment_by_id(comment_id).await {
+            Ok(comment) => Ok(comment),
+            Err(sqlx::Error::RowNotFound) => Err(comment_not_found()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_comments(
+        &self,
+        reel_id: Uuid,
+        sort: CommentSort,
+        cursor: Option<String>,
+        limit: u32,
+    ) -> Result<CommentPage, AppError> {
+        let cursor = parse_comment_cursor(cursor, sort)?;
+        let limit = page_size(limit);
+        self.check_reel(reel_id).await?;
+
+        match self
+            .db
+            .comments
+            .get_comments_by_reel_id_after_cursor(reel_id, sort, cursor.as_ref(), limit as i64 + 1)
+            .await
+        {
+            Ok(comments) => Ok(into_comment_page(comments, limit, sort)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_replies(&self, comment_id: Uuid, cursor: Option<String>, limit: u32) -> Result<CommentPage, AppError> {
+        let cursor = parse_comment_cursor(cursor, CommentSort::Newest)?;
+        let limit = page_size(limit);
+        self.get_comment_by_id(comment_id).await?;
+
+        match self.db.comments.get_replies_after_cursor(comment_id, cursor.as_ref(), limit as i64 + 1).await {
+            Ok(replies) => Ok(into_comment_page(replies, limit, CommentSort::Newest)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn post_comment(&self, reel_id: Uuid, comment: PostComment, user: &AuthenticatedUser) -> Result<Comment, AppError> {
+        let body = comment_body(&comment.body)?;
+        self.check_reel(reel_id).await?;
+        if let Some(parent_id) = comment.parent_id {
+            self.check_parent(reel_id, parent_id).await?;
+        }
+
+        let comment = Comment {
+            id: Uuid::new_v4(),
+            reel_id,
+            parent_id: comment.parent_id,
+            author_id: user.id,
+            body,
+            reply_count: 0,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+        };
+
+        match self.db.comments.post_comment(&comment).await {
+            Ok(comment) => Ok(comment),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn put_comment(&self, comment_id: Uuid, comment: PutComment, user: &AuthenticatedUser) -> Result<Comment, AppError> {
+        let body = comment_body(&comment.body)?;
+        let existing = self.get_comment_by_id(comment_id).await?;
+        if existing.deleted_at.is_some() {
+            return Err(comment_not_found());
+        }
+        // moderators may delete a comment but never put words in someone else's mouth
+        if existing.author_id != user.id {
+            self.audit
+                .record(user, "comment.update", Some(existing.id), Some(existing.author_id), AuditOutcome::Denied)
+                .await;
+            return Err(AppError::Forbidden(ErrorCode::CommentForbidden, "Only the author can edit this comment".into()));
+        }
+
+        match self.db.comments.put_comment(comment_id, &body).await {
+            Ok(comment) => Ok(comment),
+            // deleted in the meantime
+            Err(sqlx::Error::RowNotFound) => Err(comment_not_found()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The author, the owner of the reel and moderators may delete a comment.
+    async fn delete_comment(&self, comment_id: Uuid, user: &AuthenticatedUser) -> Result<(), AppError> {
+        let comment = self.get_comment_by_id(comment_id).await?;
+        if comment.deleted_at.is_some() {
+            return Ok(());
+        }
+
+        let reel_owner_id = match self.db.reels.get_reel_by_id(comment.reel_id).await {
+            Ok(reel) => Some(reel.posting_user_id),
+            Err(sqlx::Error::RowNotFound) => None,
+            Err(e) => return Err(e.into()),
+        };
+        if reel_owner_id != Some(user.id)
+            && !self.audit.authorize_owner(user, "comment.delete", comment.id, comment.author_id).await
+        {
+            return Err(AppError::Forbidden(ErrorCode::CommentForbidden, "Only the author or the reel owner can delete this comment".into()));
+        }
+
+        match self.db.comments.delete_comment(comment_id).await {
+            Ok(()) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+
+    use super::*;
+
+    fn comment(seconds: i64, reply_count: i32) -> Comment {
+        Comment {
+            id: Uuid::new_v4(),
+            reel_id: Uuid::new_v4(),
+            parent_id: None,
+            author_id: Uuid::new_v4(),
+            body: "Tasty".into(),
+            reply_count,
+            created_at: DateTime::from_timestamp(seconds, 0).unwrap(),
+            updated_at: None,
+            deleted_at: None,
+        }
+    }
+
+    fn invalid_comment(body: &str) -> bool {
+        matches!(comment_body(body), Err(AppError::BadRequest(ErrorCode::InvalidComment, _)))
+    }
+
+    #[test]
+    fn comment_body_is_trimmed() {
+        assert_eq!(comment_body("  Needs more salt\n").unwrap(), "Needs more salt");
+    }
+
+    #[test]
+    fn comment_body_must_not_be_blank() {
+        assert!(invalid_comment(""));
+        assert!(invalid_comment(" \n\t "));
+    }
+
+    #[test]
+    fn comment_body_length_counts_characters() {
+        assert_eq!(comment_body(&"ż".repeat(MAX_COMMENT_LENGTH)).unwrap().chars().count(), MAX_COMMENT_LENGTH);
+        assert_eq!(comment_body(&format!("  {}  ", "a".repeat(MAX_COMMENT_LENGTH))).unwrap().len(), MAX_COMMENT_LENGTH);
+        assert!(invalid_comment(&"a".repeat(MAX_COMMENT_LENGTH + 1)));
+    }
+
+    #[test]
+    fn cursors_only_fit_the_order_they_came_from() {
+        let comment = comment(1_000, 3);
+        let newest = CommentCursor::after(&comment, false).encode();
+        let top = CommentCursor::after(&comment, true).encode();
+
+        assert!(parse_comment_cursor(None, CommentSort::Top).unwrap().is_none());
+        assert_eq!(parse_comment_cursor(Some(newest.clone()), CommentSort::Newest).unwrap().unwrap().reply_count, None);
+        assert_eq!(parse_comment_cursor(Some(top.clone()), CommentSort::Top).unwrap().unwrap().reply_count, Some(3));
+        for (token, sort) in [(newest, CommentSort::Top), (top, CommentSort::Newest), ("junk".into(), CommentSort::Newest)] {
+            assert!(matches!(
+                parse_comment_cursor(Some(token), sort),
+                Err(AppError::BadRequest(ErrorCode::InvalidCursor, _))
+            ));
+        }
+    }
+
+    #[test]
+    fn page_has_a_cursor_only_when_more_comments_follow() {
+        let comments: Vec<Comment> = (0..3).map(|i| comment(1_000 - i, 5 - i as i32)).collect();
+
+        let page = into_comment_page(comments.clone(), 3, CommentSort::Newest);
+        assert_eq!(page.comments.len(), 3);
+        assert!(page.next_cursor.is_none());
+
+        let page = into_comment_page(comments.clone(), 2, CommentSort::Top);
+        assert_eq!(page.comments.len(), 2);
+        let cursor = CommentCursor::decode(&page.next_cursor.unwrap()).unwrap();
+        assert_eq!(cursor, CommentCursor::after(&comments[1], true));
+    }
+}
@@ -0,0 +1,120 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser, dao::database_context::Database, error::{error::AppError, problem::ErrorCode}, model::{ReactionKind, Reel, ReelCursor, ReelEngagement, ReelPage}, service::reel_service::{page_size, parse_cursor}
+};
+
+#[async_trait]
+pub trait EngagementRepository<'a> {
+    fn new(db: Arc<Database<'a>>) -> Self;
+    async fn like_reel(&self, user: &AuthenticatedUser, reel_id: Uuid) -> Result<ReelEngagement, AppError>;
+    async fn unlike_reel(&self, user: &AuthenticatedUser, reel_id: Uuid) -> Result<ReelEngagement, AppError>;
+    async fn save_reel(&self, user: &AuthenticatedUser, reel_id: Uuid) -> Result<ReelEngagement, AppError>;
+    async fn unsave_reel(&self, user: &AuthenticatedUser, reel_id: Uuid) -> Result<ReelEngagement, AppError>;
+    async fn get_saved_reels(&self, user: &AuthenticatedUser, cursor: Option<String>, limit: u32) -> Result<ReelPage, AppError>;
+    async fn mark_reels<'r>(&self, viewer: Option<&AuthenticatedUser>, reels: Vec<&'r mut Reel>) -> Result<(), AppError>;
+}
+
+pub struct EngagementService<'a> {
+    pub db: Arc<Database<'a>>,
+}
+
+impl EngagementService<'_> {
+    /// Adds or removes a reaction and returns the counters as they are afterwards.
+    async fn react(
+        &self,
+        kind: ReactionKind,
+        user: &AuthenticatedUser,
+        reel_id: Uuid,
+        add: bool,
+    ) -> Result<ReelEngagement, AppError> {
+        let reactions = &self.db.reactions;
+        let result = async {
+            let mut tx = self.db.begin().await?;
+            if add {
+                reactions.post_reaction_with(&mut *tx, kind, user.id, reel_id).await?;
+            } else {
+                reactions.delete_reaction_with(&mut *tx, kind, user.id, reel_id).await?;
+            }
+            let engagement = reactions.get_engagement_with(&mut *tx, user.id, reel_id).await?;
+            tx.commit().await?;
+            Ok::<_, sqlx::Error>(engagement)
+        }
+        .await;
+
+        match result {
+            Ok(Some(engagement)) => Ok(engagement),
+            Ok(None) => Err(AppError::NotFound(ErrorCode::ReelNotFound, "Reel not found".into())),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl<'a> EngagementRepository<'a> for EngagementService<'a> {
+    fn new(db: Arc<Database<'a>>) -> Self {
+        EngagementService { db }
+    }
+
+    async fn like_reel(&self, user: &AuthenticatedUser, reel_id: Uuid) -> Result<ReelEngagement, AppError> {
+        self.react(ReactionKind::Like, user, reel_id, true).await
+    }
+
+    async fn unlike_reel(&self, user: &AuthenticatedUser, reel_id: Uuid) -> Result<ReelEngagement, AppError> {
+        self.react(ReactionKind::Like, user, reel_id, false).await
+    }
+
+    async fn save_reel(&self, user: &AuthenticatedUser, reel_id: Uuid) -> Result<ReelEngagement, AppError> {
+        self.react(ReactionKind::Save, user, reel_id, true).await
+    }
+
+    async fn unsave_reel(&self, user: &AuthenticatedUser, reel_id: Uuid) -> Result<ReelEngagement, AppError> {
+        self.react(ReactionKind::Save, user, reel_id, false).await
+    }
+
+    async fn get_saved_reels(&self, user: &AuthenticatedUser, cursor: Option<String>, limit: u32) -> Result<ReelPage, AppError> {
+        let cursor = parse_cursor(cursor)?;
+        let limit = page_size(limit);
+
+        let mut saved = match self.db.reactions.get_saved_reels_after_cursor(user.id, cursor.as_ref(), limit as i64 + 1).await {
+            Ok(saved) => saved,
+            Err(e) => return Err(e.into()),
+        };
+        let next_cursor = if saved.len() > limit as usize {
+            saved.truncate(limit as usize);
+            saved.last().map(|(reel, saved_at)| {
+                ReelCursor { creation_timestamp: saved_at.naive_utc(), id: reel.id, rank: None }.encode()
+            })
+        } else {
+            None
+        };
+
+        let mut reels: Vec<Reel> = saved.into_iter().map(|(reel, _)| reel).collect();
+        self.mark_reels(Some(user), reels.iter_mut().collect()).await?;
+        Ok(ReelPage { reels, next_cursor })
+    }
+
+    /// Fills in `liked_by_me` and `saved_by_me`. Does nothing for anonymous viewers.
+    async fn mark_reels<'r>(&self, viewer: Option<&AuthenticatedUser>, reels: Vec<&'r mut Reel>) -> Result<(), AppError> {
+        let Some(viewer) = viewer else {
+            return Ok(());
+        };
+        if reels.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<Uuid> = reels.iter().map(|reel| reel.id).collect();
+        let reactions = &self.db.reactions;
+        let liked = reactions.get_reacted_reel_ids(ReactionKind::Like, viewer.id, &ids).await?;
+        let saved = reactions.get_reacted_reel_ids(ReactionKind::Save, viewer.id, &ids).await?;
+
+        for reel in reels {
+            reel.liked_by_me = Some(liked.contains(&reel.id));
+            reel.saved_by_me = Some(saved.contains(&reel.id));
+        }
+        Ok(())
+    }
+}
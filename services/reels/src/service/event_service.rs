@@ -0,0 +1,150 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser, dao::database_context::Database, error::{error::AppError, problem::ErrorCode}, model::{EventKind, EventReceipt, PostReelEvents, ReelEvent, ReelView}
+};
+
+/// Most events accepted in one batch.
+pub const MAX_EVENTS_PER_BATCH: usize = 100;
+/// Longest session id kept, in characters.
+pub const MAX_SESSION_ID_LENGTH: usize = 64;
+/// Events of a viewer less than this many minutes apart belong to the same view.
+pub const VIEW_GAP_MINUTES: i64 = 30;
+/// Oldest event accepted, in hours, so a client cannot rewrite old statistics.
+pub const MAX_EVENT_AGE_HOURS: i64 = 24;
+/// Largest `watch_ms` accepted, a day.
+const MAX_WATCH_MS: i64 = 24 * 60 * 60 * 1000;
+
+fn invalid_event(message: String) -> AppError {
+    AppError::BadRequest(ErrorCode::InvalidEvent, message)
+}
+
+/// Share of the video an event says was watched.
+fn completion(kind: EventKind, watch_ms: i64, video_length_seconds: i32) -> f64 {
+    if kind == EventKind::Complete {
+        return 1.0;
+    }
+    if video_length_seconds <= 0 {
+        return 0.0;
+    }
+    (watch_ms as f64 / (video_length_seconds as f64 * 1000.0)).min(1.0)
+}
+
+#[async_trait]
+pub trait EventRepository<'a> {
+    fn new(db: Arc<Database<'a>>) -> Self;
+    async fn post_events(&self, viewer: Option<&AuthenticatedUser>, batch: PostReelEvents) -> Result<EventReceipt, AppError>;
+}
+
+pub struct EventService<'a> {
+    pub db: Arc<Database<'a>>,
+}
+
+#[async_trait]
+impl<'a> EventRepository<'a> for EventService<'a> {
+    fn new(db: Arc<Database<'a>>) -> Self {
+        EventService { db }
+    }
+
+    /// Stores the events and folds them into the views of their reels. A view starts with the first
+    /// event of a viewer and lasts while their events keep coming less than `VIEW_GAP_MINUTES` apart.
+    async fn post_events(&self, viewer: Option<&AuthenticatedUser>, batch: PostReelEvents) -> Result<EventReceipt, AppError> {
+        let session_id = batch.session_id.trim();
+        if session_id.is_empty() || session_id.chars().count() > MAX_SESSION_ID_LENGTH {
+            return Err(invalid_event(format!(
+                "session_id must have between 1 and {} characters",
+                MAX_SESSION_ID_LENGTH
+            )));
+        }
+        if batch.events.len() > MAX_EVENTS_PER_BATCH {
+            return Err(invalid_event(format!("A batch can have at most {} events", MAX_EVENTS_PER_BATCH)));
+        }
+
+        let now = Utc::now();
+        let oldest = now - Duration::hours(MAX_EVENT_AGE_HOURS);
+        let mut events = Vec::with_capacity(batch.events.len());
+        for event in batch.events {
+            if !(0..=MAX_WATCH_MS).contains(&event.watch_ms) {
+                return Err(invalid_event(format!("watch_ms must be between 0 and {}", MAX_WATCH_MS)));
+            }
+            // clocks of clients run ahead
+            let occurred_at = event.occurred_at.unwrap_or(now).min(now);
+            if occurred_at < oldest {
+                return Err(invalid_event(format!(
+                    "Events older than {} hours are not accepted",
+                    MAX_EVENT_AGE_HOURS
+                )));
+            }
+            events.push(ReelEvent {
+                id: 0,
+                reel_id: event.reel_id,
+                user_id: viewer.map(|user| user.id),
+                session_id: session_id.to_string(),
+                kind: event.kind,
+                watch_ms: event.watch_ms,
+                occurred_at,
+                received_at: now,
+            });
+        }
+
+        let mut reel_ids: Vec<Uuid> = events.iter().map(|event| event.reel_id).collect();
+        reel_ids.sort();
+        reel_ids.dedup();
+        let lengths: HashMap<Uuid, i32> = self.db.events.get_video_lengths(&reel_ids).await?.into_iter().collect();
+        events.retain(|event| lengths.contains_key(&event.reel_id));
+        // reel order keeps the viewer locks of concurrent batches in the same order
+        events.sort_by_key(|event| (event.reel_id, event.occurred_at));
+
+        let table = &self.db.events;
+        let result = async {
+            let mut tx = self.db.begin().await?;
+            for event in &events {
+                table.post_event_with(&mut *tx, event).await?;
+
+                let viewer = event.viewer();
+                let completion = completion(event.kind, event.watch_ms, lengths[&event.reel_id]);
+                table.lock_viewer_with(&mut *tx, event.reel_id, &viewer).await?;
+                let since = event.occurred_at - Duration::minutes(VIEW_GAP_MINUTES);
+
+                match table.get_open_view_with(&mut *tx, event.reel_id, &viewer, since).await? {
+                    Some(mut view) => {
+                        let added = (completion - view.completion).max(0.0);
+                        view.last_seen_at = view.last_seen_at.max(event.occurred_at);
+                        view.watch_ms = view.watch_ms.max(event.watch_ms);
+                        view.completion += added;
+                        table.put_view_with(&mut *tx, &view).await?;
+                        if added > 0.0 {
+                            table.add_reel_views_with(&mut *tx, event.reel_id, 0, added).await?;
+                        }
+                    }
+                    None => {
+                        let view = ReelView {
+                            id: Uuid::new_v4(),
+                            reel_id: event.reel_id,
+                            viewer,
+                            user_id: event.user_id,
+                            started_at: event.occurred_at,
+                            last_seen_at: event.occurred_at,
+                            watch_ms: event.watch_ms,
+                            completion,
+                        };
+                        table.post_view_with(&mut *tx, &view).await?;
+                        table.add_reel_views_with(&mut *tx, event.reel_id, 1, completion).await?;
+                    }
+                }
+            }
+            tx.commit().await?;
+            Ok::<_, sqlx::Error>(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => Ok(EventReceipt { accepted: events.len() as u32 }),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
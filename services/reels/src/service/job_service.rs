@@ -0,0 +1,232 @@
+use async_trait::async_trait;
+use log::{error, info, warn};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser, config::JobSettings, dao::database_context::{Database, Table}, error::{error::AppError, problem::ErrorCode}, model::{JobStatus, MediaJob, MediaTask}, service::{audit_service::AuditLog, reel_service::page_size, transcode_service::TranscodeService, video_service::VideoService}, storage::VideoStorage
+};
+
+/// Enqueues background work into the `media_jobs` table.
+#[derive(Clone)]
+pub struct JobQueue<'a> {
+    jobs: Arc<Table<'a, MediaJob>>,
+    max_attempts: i32,
+}
+
+impl<'a> JobQueue<'a> {
+    pub fn new(db: &Database<'a>, settings: &JobSettings) -> Self {
+        JobQueue {
+            jobs: db.jobs.clone(),
+            max_attempts: settings.max_attempts,
+        }
+    }
+
+    pub async fn enqueue(&self, task: &MediaTask) -> Result<Uuid, AppError> {
+        let payload = serde_json::to_value(task).map_err(|e| AppError::InternalError(e.to_string()))?;
+
+        match self.jobs.enqueue_job(task.kind(), &payload, self.max_attempts).await {
+            Ok(job_id) => Ok(job_id),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Schedules removal of stored objects. A failure only leaves orphaned objects behind,
+    /// so it is logged instead of failing the request that replaced them.
+    pub async fn cleanup(&self, keys: Vec<String>, prefixes: Vec<String>) {
+        if keys.is_empty() && prefixes.is_empty() {
+            return;
+        }
+        if let Err(e) = self.enqueue(&MediaTask::Cleanup { keys: keys.clone(), prefixes: prefixes.clone() }).await {
+            warn!("Failed to schedule removal of {:?} {:?}: {}", keys, prefixes, e);
+        }
+    }
+}
+
+#[async_trait]
+pub trait JobRepository<'a> {
+    fn new(db: Arc<Database<'a>>) -> Self;
+    async fn get_jobs_paginated(
+        &self,
+        user: &AuthenticatedUser,
+        status: Option<String>,
+        kind: Option<String>,
+        page: u32,
+        limit: u32,
+    ) -> Result<Vec<MediaJob>, AppError>;
+    async fn retry_job(&self, user: &AuthenticatedUser, job_id: Uuid) -> Result<MediaJob, AppError>;
+}
+
+pub struct JobService<'a> {
+    pub db: Arc<Database<'a>>,
+    pub audit: AuditLog<'a>,
+}
+
+fn admin_only() -> AppError {
+    AppError::Forbidden(ErrorCode::Forbidden, "Only admins can manage jobs".into())
+}
+
+#[async_trait]
+impl<'a> JobRepository<'a> for JobService<'a> {
+    fn new(db: Arc<Database<'a>>) -> Self {
+        let audit = AuditLog::new(&db);
+        JobService { db, audit }
+    }
+
+    async fn get_jobs_paginated(
+        &self,
+        user: &AuthenticatedUser,
+        status: Option<String>,
+        kind: Option<String>,
+        page: u32,
+        limit: u32,
+    ) -> Result<Vec<MediaJob>, AppError> {
+        if !self.audit.authorize_admin(user, "job.list", None).await {
+            return Err(admin_only());
+        }
+        let status = status
+            .map(|s| s.parse::<JobStatus>())
+            .transpose()
+            .map_err(|e| AppError::BadRequest(ErrorCode::InvalidQuery, e))?;
+
+        let limit = page_size(limit);
+        let offset = page.saturating_sub(1) as i64 * limit as i64;
+        let limit = limit as i64;
+
+        match self
+            .db
+            .jobs
+            .get_jobs_paginated(status.as_ref().map(JobStatus::as_str), kind.as_deref(), offset, limit)
+            .await
+        {
+            Ok(jobs) => Ok(jobs),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn retry_job(&self, user: &AuthenticatedUser, job_id: Uuid) -> Result<MediaJob, AppError> {
+        if !self.audit.authorize_admin(user, "job.retry", Some(job_id)).await {
+            return Err(admin_only());
+        }
+
+        let job = match self.db.jobs.get_job_by_id(job_id).await {
+            Ok(job) => job,
+            Err(sqlx::Error::RowNotFound) => return Err(AppError::NotFound(ErrorCode::JobNotFound, "Job not found".into())),
+            Err(e) => return Err(e.into()),
+        };
+
+        match self.db.jobs.retry_job(job_id).await {
+            Ok(Some(job)) => Ok(job),
+            Ok(None) => Err(AppError::BadRequest(ErrorCode::JobNotRetryable, format!("A {} job cannot be retried", job.status))),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Pool of workers polling `media_jobs` and running the claimed tasks.
+pub struct JobWorker<'a> {
+    pub db: Arc<Database<'a>>,
+    pub settings: JobSettings,
+    pub storage: Arc<dyn VideoStorage>,
+    pub videos: Arc<VideoService<'a>>,
+    pub transcoder: TranscodeService<'a>,
+}
+
+impl<'a> JobWorker<'a> {
+    pub fn new(
+        db: Arc<Database<'a>>,
+        settings: JobSettings,
+        storage: Arc<dyn VideoStorage>,
+        videos: Arc<VideoService<'a>>,
+        transcoder: TranscodeService<'a>,
+    ) -> Self {
+        JobWorker { db, settings, storage, videos, transcoder }
+    }
+
+    async fn run_task(&self, task: MediaTask, final_attempt: bool) -> Result<(), AppError> {
+        match task {
+            MediaTask::Transcode { video_id } => self.transcoder.process(video_id, final_attempt).await,
+            MediaTask::Thumbnail { video_id, offset_seconds } => {
+                match self.videos.regenerate_poster(video_id, offset_seconds).await {
+                    Err(AppError::NotFound(..)) => Ok(()),
+                    result => result.map(|_| ()),
+                }
+            }
+            MediaTask::Cleanup { keys, prefixes } => {
+                for key in keys {
+                    self.storage.delete_if_exists(&key).await?;
+                }
+                for prefix in prefixes {
+                    self.storage.delete_prefix(&prefix).await?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn run_job(&self, job: MediaJob) {
+        let final_attempt = job.attempts >= job.max_attempts;
+        let result = match serde_json::from_value::<MediaTask>(job.payload.clone()) {
+            Ok(task) => self.run_task(task, final_attempt).await,
+            // A payload that cannot be read will not get better with retries.
+            Err(e) => {
+                if let Err(e) = self.db.jobs.bury_job(job.id, job.attempts, &format!("Invalid payload: {}", e)).await {
+                    error!("Failed to bury job {}: {}", job.id, e);
+                }
+                return;
+            }
+        };
+
+        let update = match result {
+            Ok(()) => self.db.jobs.complete_job(job.id, job.attempts).await,
+            Err(e) if final_attempt => {
+                error!("Job {} ({}) failed for the last time: {}", job.id, job.kind, e);
+                self.db.jobs.bury_job(job.id, job.attempts, &e.to_string()).await
+            }
+            Err(e) => {
+                let delay = self.settings.backoff_seconds(job.attempts);
+                warn!("Job {} ({}) failed, retrying in {}s: {}", job.id, job.kind, delay, e);
+                self.db.jobs.retry_job_later(job.id, job.attempts, &e.to_string(), delay).await
+            }
+        };
+        match update {
+            Ok(0) => warn!("Job {} lost its lease before finishing, outcome dropped", job.id),
+            Ok(_) => {}
+            Err(e) => error!("Failed to record the outcome of job {}: {}", job.id, e),
+        }
+    }
+
+    async fn work(&self, worker: usize) {
+        let poll_interval = Duration::from_millis(self.settings.poll_interval_ms);
+        loop {
+            match self.db.jobs.claim_job(self.settings.lease_seconds).await {
+                Ok(Some(job)) => {
+                    info!("Worker {} running job {} ({}), attempt {}", worker, job.id, job.kind, job.attempts);
+                    self.run_job(job).await;
+                }
+                Ok(None) => tokio::time::sleep(poll_interval).await,
+                Err(e) => {
+                    error!("Worker {} failed to claim a job: {}", worker, e);
+                    tokio::time::sleep(poll_interval).await;
+                }
+            }
+        }
+    }
+}
+
+impl JobWorker<'static> {
+    /// Queues transcodes for videos that never got one, then spawns the workers.
+    pub async fn start(self) {
+        match self.db.jobs.enqueue_missing_transcodes(self.settings.max_attempts).await {
+            Ok(0) => {}
+            Ok(queued) => info!("Queued {} videos waiting for renditions", queued),
+            Err(e) => error!("Failed to queue videos waiting for renditions: {}", e),
+        }
+
+        let worker = Arc::new(self);
+        for id in 0..worker.settings.workers.max(1) {
+            let worker = worker.clone();
+            tokio::spawn(async move { worker.work(id).await });
+        }
+    }
+}
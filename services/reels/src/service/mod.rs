@@ -1,2 +1,11 @@
+pub mod audit_service;
+pub mod comment_service;
+pub mod engagement_service;
+pub mod event_service;
+pub mod job_service;
 pub mod reel_service;
+pub mod stats_service;
+pub mod tag_service;
+pub mod transcode_service;
+pub mod upload_service;
 pub mod video_service;
\ No newline at end of file
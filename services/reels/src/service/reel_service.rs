@@ -1,16 +1,15 @@
 use async_trait::async_trait;
 use sqlx::types::chrono::{NaiveDateTime, Utc};
-use tokio::fs;
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
-    dao::database_context::Database, error::error::AppError, model::{PostReel, Reel, ReelWithVideos}
+    auth::AuthenticatedUser, dao::database_context::Database, error::{error::AppError, problem::ErrorCode}, model::{PostReel, PostVideo, Reel, ReelCursor, ReelFeed, ReelPage, ReelSearch, ReelSearchPage, ReelWithVideo, ReelWithVideos}, service::{audit_service::AuditLog, job_service::JobQueue, tag_service::reel_tags, video_service::VideoService}, util::stream_to_file::TempUpload
 };
 
 #[async_trait]
 pub trait ReelRepository<'a>: Send + Sync {
-    fn new(db: Arc<Database<'a>>) -> Self;
+    fn new(db: Arc<Database<'a>>, jobs: JobQueue<'a>, videos: Arc<VideoService<'a>>) -> Self;
     async fn get_reel_by_id(&self, reel_id: Uuid) -> Result<Reel, AppError>;
     async fn get_reels_paginated(
         &self,
@@ -28,32 +27,151 @@ pub trait ReelRepository<'a>: Send + Sync {
         page: u32,
         limit: u32,
     ) -> Result<ReelWithVideos, AppError>;
-    async fn post_reel(&self, reel: PostReel, posting_user_id: Uuid, video_id: Option<Uuid>) -> Result<(), AppError>;
-    // async fn post_reel_with_video(
-    //     &self,
-    //     reel: PostReel,
-    //     video: PostVideo,
-    //     file: BytesMut,
-    //     file_name: String,
-    // ) -> Result<(), AppError>;
-    // async fn put_reel(&self, reel: PostReel, posting_user_id: Uuid) -> Result<(), actix_web::Error>;
-    async fn delete_reel_with_video(&self, reel_id: Uuid) -> Result<(), AppError>;
+    async fn get_reels_after_cursor(&self, cursor: Option<String>, limit: u32) -> Result<ReelPage, AppError>;
+    async fn get_reels_by_user_id_after_cursor(
+        &self,
+        user_id: Uuid,
+        cursor: Option<String>,
+        limit: u32,
+    ) -> Result<ReelPage, AppError>;
+    async fn get_reels_with_videos_after_cursor(
+        &self,
+        cursor: Option<String>,
+        limit: u32,
+    ) -> Result<ReelWithVideos, AppError>;
+    async fn get_feed(&self, cursor: Option<String>, limit: u32) -> Result<ReelFeed, AppError>;
+    async fn search_reels(&self, search: ReelSearch, cursor: Option<String>, limit: u32) -> Result<ReelSearchPage, AppError>;
+    async fn post_reel(&self, reel: PostReel, posting_user_id: Uuid, video_id: Option<Uuid>) -> Result<Reel, AppError>;
+    async fn post_reel_with_video(
+        &self,
+        reel: PostReel,
+        video: PostVideo,
+        posting_user_id: Uuid,
+        upload: TempUpload,
+        cover: Option<TempUpload>,
+    ) -> Result<ReelWithVideo, AppError>;
+    async fn put_reel(&self, reel_id: Uuid, reel: PostReel, user: &AuthenticatedUser) -> Result<Reel, AppError>;
+    async fn delete_reel_with_video(&self, reel_id: Uuid, user: &AuthenticatedUser) -> Result<Reel, AppError>;
 }
 
 pub struct ReelService<'a> {
     pub db: Arc<Database<'a>>,
+    pub jobs: JobQueue<'a>,
+    pub videos: Arc<VideoService<'a>>,
+    pub audit: AuditLog<'a>,
+}
+
+impl ReelService<'_> {
+    /// Loads the reel if the user posted it or is a moderator.
+    async fn check_owner(&self, reel_id: Uuid, user: &AuthenticatedUser, action: &str) -> Result<Reel, AppError> {
+        match self.db.reels.get_reel_by_id(reel_id).await {
+            Ok(reel) => {
+                if !self.audit.authorize_owner(user, action, reel.id, reel.posting_user_id).await {
+                    return Err(AppError::Forbidden(ErrorCode::ReelForbidden, "Only the posting user can change this reel".into()));
+                }
+                Ok(reel)
+            }
+            Err(sqlx::Error::RowNotFound) => Err(AppError::NotFound(ErrorCode::ReelNotFound, "Reel not found".into())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// A video named in the request body must exist and belong to `owner_id`.
+    async fn check_video_owner(&self, video_id: Uuid, owner_id: Uuid) -> Result<(), AppError> {
+        match self.db.videos.get_video_by_id(video_id).await {
+            Ok(video) if video.posting_user_id != owner_id => {
+                Err(AppError::Forbidden(ErrorCode::VideoForbidden, "Video belongs to another user".into()))
+            }
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::RowNotFound) => Err(AppError::BadRequest(ErrorCode::VideoNotFound, "Video not found".into())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// A recipe named in the request body must exist, so this is a bad request rather than a 404.
+    async fn check_recipe(&self, recipe_id: Uuid) -> Result<(), AppError> {
+        match self.db.recipe_videos.recipe_exists(recipe_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(AppError::BadRequest(ErrorCode::RecipeNotFound, "Recipe not found".into())),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Largest page any feed returns, whatever the client asks for.
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+pub(crate) fn page_size(limit: u32) -> u32 {
+    limit.clamp(1, MAX_PAGE_SIZE)
+}
+
+pub(crate) fn parse_cursor(cursor: Option<String>) -> Result<Option<ReelCursor>, AppError> {
+    cursor
+        .map(|token| {
+            ReelCursor::decode(&token)
+                .ok_or_else(|| AppError::BadRequest(ErrorCode::InvalidCursor, "Invalid pagination cursor".into()))
+        })
+        .transpose()
+}
+
+/// Most words of a search query that are matched, the rest are ignored.
+const MAX_SEARCH_TERMS: usize = 10;
+
+/// Turns the text a user typed into a `to_tsquery` expression matching reels containing every word,
+/// the last one possibly unfinished. Punctuation is dropped so it cannot be read as tsquery operators.
+fn prefix_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .take(MAX_SEARCH_TERMS)
+        .map(|term| format!("{}:*", term.to_lowercase()))
+        .collect();
+
+    if terms.is_empty() { None } else { Some(terms.join(" & ")) }
+}
+
+/// Trims the extra row fetched to find out whether another page follows, and points the cursor at the last reel kept.
+pub(crate) fn into_page(mut reels: Vec<Reel>, limit: u32) -> ReelPage {
+    let next_cursor = if reels.len() > limit as usize {
+        reels.truncate(limit as usize);
+        reels.last().map(|reel| ReelCursor::from(reel).encode())
+    } else {
+        None
+    };
+    ReelPage { reels, next_cursor }
+}
+
+fn new_reel(reel: PostReel, posting_user_id: Uuid, video_id: Uuid) -> Reel {
+    let timestamp: NaiveDateTime = Utc::now().naive_utc();
+
+    Reel {
+        id: Uuid::new_v4(),
+        video_id,
+        posting_user_id,
+        title: reel.title,
+        description: reel.description,
+        creation_timestamp: timestamp,
+        like_count: 0,
+        save_count: 0,
+        view_count: 0,
+        completion_rate: None,
+        liked_by_me: None,
+        saved_by_me: None,
+    }
 }
 
 #[async_trait]
 impl<'a> ReelRepository<'a> for ReelService<'a> {
-    fn new(db: Arc<Database<'a>>) -> Self {
-        ReelService { db }
+    fn new(db: Arc<Database<'a>>, jobs: JobQueue<'a>, videos: Arc<VideoService<'a>>) -> Self {
+        let audit = AuditLog::new(&db);
+        ReelService { db, jobs, videos, audit }
     }
 
     async fn get_reel_by_id(&self, reel_id: Uuid) -> Result<Reel, AppError> {
         match self.db.reels.get_reel_by_id(reel_id).await {
             Ok(reels) => Ok(reels),
-            Err(e) => Err(AppError::InternalError(e.to_string())),
+            Err(sqlx::Error::RowNotFound) => Err(AppError::NotFound(ErrorCode::ReelNotFound, "Reel not found".into())),
+            Err(e) => Err(e.into()),
         }
     }
 
@@ -62,12 +180,13 @@ impl<'a> ReelRepository<'a> for ReelService<'a> {
         page: u32,
         limit: u32,
     ) -> Result<Vec<Reel>, AppError> {
-        let offset = (page.saturating_sub(1) * limit) as i64;
+        let limit = page_size(limit);
+        let offset = page.saturating_sub(1) as i64 * limit as i64;
         let limit = limit as i64;
 
         match self.db.reels.get_reels_paginated(offset, limit).await {
             Ok(reels) => Ok(reels),
-            Err(e) => Err(AppError::InternalError(e.to_string())),
+            Err(e) => Err(e.into()),
         }
     }
 
@@ -77,12 +196,13 @@ impl<'a> ReelRepository<'a> for ReelService<'a> {
         page: u32,
         limit: u32,
     ) -> Result<Vec<Reel>, AppError> {
-        let offset = (page.saturating_sub(1) * limit) as i64;
+        let limit = page_size(limit);
+        let offset = page.saturating_sub(1) as i64 * limit as i64;
         let limit = limit as i64;
 
         match self.db.reels.get_reels_by_user_id_paginated(user_id, offset, limit).await {
             Ok(reels) => Ok(reels),
-            Err(e) => Err(AppError::InternalError(e.to_string())),
+            Err(e) => Err(e.into()),
         }
     }
 
@@ -91,53 +211,222 @@ impl<'a> ReelRepository<'a> for ReelService<'a> {
         page: u32,
         limit: u32,
     ) -> Result<ReelWithVideos, AppError> {
-        let offset = (page.saturating_sub(1) * limit) as i64;
+        let limit = page_size(limit);
+        let offset = page.saturating_sub(1) as i64 * limit as i64;
         let limit = limit as i64;
 
         match self.db.reels.get_reels_with_videos_paginated(offset, limit).await {
             Ok(reels_with_videos) => Ok(reels_with_videos),
-            Err(e) => Err(AppError::InternalError(e.to_string())),
-        }
-    }    async fn post_reel(&self, reel: PostReel, posting_user_id: Uuid, video_id: Option<Uuid>) -> Result<(), AppError> {
-        let reel_id: Uuid = Uuid::new_v4();
-        let timestamp: NaiveDateTime = Utc::now().naive_utc();
-
-        let reel: Reel = Reel {
-            id: reel_id,
-            video_id: video_id.unwrap_or(Uuid::new_v4()),
-            posting_user_id: posting_user_id,
-            title: reel.title,
-            description: reel.description,
-            creation_timestamp: timestamp,
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_reels_after_cursor(&self, cursor: Option<String>, limit: u32) -> Result<ReelPage, AppError> {
+        let cursor = parse_cursor(cursor)?;
+        let limit = page_size(limit);
+
+        match self.db.reels.get_reels_after_cursor(cursor.as_ref(), limit as i64 + 1).await {
+            Ok(reels) => Ok(into_page(reels, limit)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_reels_by_user_id_after_cursor(
+        &self,
+        user_id: Uuid,
+        cursor: Option<String>,
+        limit: u32,
+    ) -> Result<ReelPage, AppError> {
+        let cursor = parse_cursor(cursor)?;
+        let limit = page_size(limit);
+
+        match self
+            .db
+            .reels
+            .get_reels_by_user_id_after_cursor(user_id, cursor.as_ref(), limit as i64 + 1)
+            .await
+        {
+            Ok(reels) => Ok(into_page(reels, limit)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_reels_with_videos_after_cursor(
+        &self,
+        cursor: Option<String>,
+        limit: u32,
+    ) -> Result<ReelWithVideos, AppError> {
+        let page = self.get_reels_after_cursor(cursor, limit).await?;
+        let video_ids: Vec<Uuid> = page.reels.iter().map(|r| r.video_id).collect();
+
+        match self.db.videos.get_videos_by_ids(&video_ids).await {
+            Ok(videos) => Ok(ReelWithVideos {
+                reels: page.reels,
+                videos,
+                next_cursor: page.next_cursor,
+            }),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_feed(&self, cursor: Option<String>, limit: u32) -> Result<ReelFeed, AppError> {
+        let cursor = parse_cursor(cursor)?;
+        let limit = page_size(limit);
+
+        let mut items = match self.db.reels.get_feed_after_cursor(cursor.as_ref(), limit as i64 + 1).await {
+            Ok(items) => items,
+            Err(e) => return Err(e.into()),
+        };
+        let has_more = items.len() > limit as usize;
+        items.truncate(limit as usize);
+        let next_cursor = if has_more {
+            items.last().map(|item| ReelCursor::from(&item.reel).encode())
+        } else {
+            None
+        };
+
+        Ok(ReelFeed { items, has_more, next_cursor })
+    }
+
+    async fn search_reels(&self, search: ReelSearch, cursor: Option<String>, limit: u32) -> Result<ReelSearchPage, AppError> {
+        let tsquery = prefix_query(&search.query)
+            .ok_or_else(|| AppError::BadRequest(ErrorCode::InvalidQuery, "Search query must contain a word".into()))?;
+        let cursor = match parse_cursor(cursor)? {
+            Some(cursor) if cursor.rank.is_none() => {
+                return Err(AppError::BadRequest(ErrorCode::InvalidCursor, "Cursor does not belong to a search".into()))
+            }
+            cursor => cursor,
+        };
+        let limit = page_size(limit);
+
+        let mut results = match self.db.reels.search_reels(&tsquery, &search, cursor.as_ref(), limit as i64 + 1).await {
+            Ok(results) => results,
+            Err(e) => return Err(e.into()),
+        };
+        let next_cursor = if results.len() > limit as usize {
+            results.truncate(limit as usize);
+            results.last().map(|hit| ReelCursor { rank: Some(hit.rank), ..ReelCursor::from(&hit.reel) }.encode())
+        } else {
+            None
+        };
+
+        Ok(ReelSearchPage { results, next_cursor })
+    }
+
+    async fn post_reel(&self, reel: PostReel, posting_user_id: Uuid, video_id: Option<Uuid>) -> Result<Reel, AppError> {
+        let video_id = match video_id.or(reel.video_id) {
+            Some(video_id) => {
+                // Deleting the reel deletes its video, so it must be one of the poster's own.
+                self.check_video_owner(video_id, posting_user_id).await?;
+                video_id
+            }
+            None => return Err(AppError::BadRequest(ErrorCode::MissingField, "video_id is required".into())),
         };
+        let recipe_id = reel.recipe_id;
+        let tags = reel_tags(&reel)?;
+        if let Some(recipe_id) = recipe_id {
+            self.check_recipe(recipe_id).await?;
+        }
+        let reel: Reel = new_reel(reel, posting_user_id, video_id);
+
+        let result = async {
+            let mut tx = self.db.begin().await?;
+            let reel = self.db.reels.post_reel_with(&mut *tx, &reel).await?;
+            self.db.tags.set_reel_tags_with(&mut *tx, reel.id, &tags).await?;
+            if let Some(recipe_id) = recipe_id {
+                self.db.recipe_videos.post_recipe_video_with(&mut *tx, recipe_id, reel.video_id).await?;
+            }
+            tx.commit().await?;
+            Ok::<_, sqlx::Error>(reel)
+        }
+        .await;
+
+        match result {
+            Ok(reel) => Ok(reel),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Stores the file, then writes the video and the reel in one transaction.
+    /// If either insert fails nothing is committed and the stored files are removed again.
+    async fn post_reel_with_video(
+        &self,
+        reel: PostReel,
+        video: PostVideo,
+        posting_user_id: Uuid,
+        upload: TempUpload,
+        cover: Option<TempUpload>,
+    ) -> Result<ReelWithVideo, AppError> {
+        let recipe_id = reel.recipe_id;
+        let tags = reel_tags(&reel)?;
+        if let Some(recipe_id) = recipe_id {
+            self.check_recipe(recipe_id).await?;
+        }
+        let staged = self.videos.stage_video(video, posting_user_id, &upload, cover.as_ref()).await?;
+        let reel: Reel = new_reel(reel, posting_user_id, staged.video.id);
+
+        let result = async {
+            let mut tx = self.db.begin().await?;
+            let video = self.db.videos.post_video_with(&mut *tx, &staged.video).await?;
+            let reel = self.db.reels.post_reel_with(&mut *tx, &reel).await?;
+            self.db.tags.set_reel_tags_with(&mut *tx, reel.id, &tags).await?;
+            if let Some(recipe_id) = recipe_id {
+                self.db.recipe_videos.post_recipe_video_with(&mut *tx, recipe_id, video.id).await?;
+            }
+            tx.commit().await?;
+            Ok::<_, sqlx::Error>(ReelWithVideo { reel, video })
+        }
+        .await;
+
+        match result {
+            Ok(created) => {
+                self.videos.publish_staged(&staged).await;
+                Ok(created)
+            }
+            Err(e) => {
+                self.videos.discard_staged(&staged).await;
+                Err(e.into())
+            }
+        }
+    }
 
-        let _ = self.db.reels.post_reel(&reel).await;
+    async fn put_reel(&self, reel_id: Uuid, reel: PostReel, user: &AuthenticatedUser) -> Result<Reel, AppError> {
+        let existing = self.check_owner(reel_id, user, "reel.update").await?;
 
-        Ok(())
+        // A moderator may edit the reel, but it can only point at videos of the user who posted it.
+        if let Some(video_id) = reel.video_id {
+            self.check_video_owner(video_id, existing.posting_user_id).await?;
+        }
+
+        let tags = reel_tags(&reel)?;
+        let result = async {
+            let mut tx = self.db.begin().await?;
+            let reel = self.db.reels.put_reel_with(&mut *tx, reel_id, &reel).await?;
+            self.db.tags.set_reel_tags_with(&mut *tx, reel.id, &tags).await?;
+            tx.commit().await?;
+            Ok::<_, sqlx::Error>(reel)
+        }
+        .await;
+
+        match result {
+            Ok(reel) => Ok(reel),
+            Err(sqlx::Error::RowNotFound) => Err(AppError::NotFound(ErrorCode::ReelNotFound, "Reel not found".into())),
+            Err(e) => Err(e.into()),
+        }
     }
 
-    // async fn post_reel_with_video(
-    //     &self,
-    //     reel: PostReel,
-    //     video: PostVideo,
-    //     file: BytesMut,
-    //     file_name: String,
-    // ) -> Result<(), AppError> {
-    //     todo!()
-    // }    async fn put_reel(&self, reel: PostReel, posting_user_id: Uuid) -> Result<(), actix_web::Error> {
-    //     todo!()
-    // }
+    async fn delete_reel_with_video(&self, reel_id: Uuid, user: &AuthenticatedUser) -> Result<Reel, AppError> {
+        let reel = self.check_owner(reel_id, user, "reel.delete").await?;
 
-    async fn delete_reel_with_video(&self, reel_id: Uuid) -> Result<(), AppError> {
         match self.db.reels.delete_reel(reel_id).await {
-            Ok(Some(video_url)) => {
-                if let Err(e) = fs::remove_file(&video_url).await {
-                    return Err(AppError::InternalError(e.to_string()))
-                }
-                Ok(())
+            Ok(Some(video)) => {
+                self.jobs
+                    .cleanup(video.storage_keys().map(str::to_string).collect(), video.hls_prefix.into_iter().collect())
+                    .await;
+                Ok(reel)
             }
-            Ok(None) => Err(AppError::InternalError("Video not found".into())),
-            Err(e) => Err(AppError::InternalError(e.to_string())),
+            Ok(None) => Err(AppError::NotFound(ErrorCode::ReelNotFound, "Reel not found".into())),
+            Err(e) => Err(e.into()),
         }
     }
 }
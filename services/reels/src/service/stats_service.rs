@@ -0,0 +1,184 @@
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, Utc};
+use log::{error, info};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser, config::StatsSettings, dao::database_context::Database, error::{error::AppError, problem::ErrorCode}, model::{CreatorStats, DailyStats, ReelDailyStats, ReelStats, StatsCounts}
+};
+
+/// Days covered when the request names no range.
+pub const DEFAULT_STATS_DAYS: i64 = 30;
+/// Longest range of days returned at once.
+pub const MAX_STATS_DAYS: i64 = 366;
+
+/// Sums of daily rollups.
+#[derive(Default, Clone, Copy)]
+struct Tally {
+    views: i64,
+    unique_viewers: i64,
+    completion_sum: f64,
+    likes: i64,
+    saves: i64,
+    comments: i64,
+}
+
+impl Tally {
+    /// Unique viewers are only right when all rows are of one reel and day, otherwise they are replaced.
+    fn add(&mut self, row: &ReelDailyStats) {
+        self.views += row.views;
+        self.unique_viewers += row.unique_viewers;
+        self.completion_sum += row.completion_sum;
+        self.likes += row.likes;
+        self.saves += row.saves;
+        self.comments += row.comments;
+    }
+
+    fn counts(&self) -> StatsCounts {
+        StatsCounts {
+            views: self.views,
+            unique_viewers: self.unique_viewers,
+            likes: self.likes,
+            saves: self.saves,
+            comments: self.comments,
+            avg_watch_percentage: (self.views > 0).then(|| self.completion_sum / self.views as f64 * 100.0),
+        }
+    }
+}
+
+/// One entry per day of the range, zero on days without activity.
+fn daily(days: &[NaiveDate], tallies: &HashMap<NaiveDate, Tally>) -> Vec<DailyStats> {
+    days.iter()
+        .map(|day| DailyStats {
+            day: *day,
+            counts: tallies.get(day).copied().unwrap_or_default().counts(),
+        })
+        .collect()
+}
+
+#[async_trait]
+pub trait StatsRepository<'a> {
+    fn new(db: Arc<Database<'a>>, settings: StatsSettings) -> Self;
+    async fn get_creator_stats(
+        &self,
+        user: &AuthenticatedUser,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<CreatorStats, AppError>;
+    async fn refresh_daily_stats(&self, since: Option<NaiveDate>) -> Result<u64, AppError>;
+}
+
+pub struct StatsService<'a> {
+    pub db: Arc<Database<'a>>,
+    settings: StatsSettings,
+}
+
+#[async_trait]
+impl<'a> StatsRepository<'a> for StatsService<'a> {
+    fn new(db: Arc<Database<'a>>, settings: StatsSettings) -> Self {
+        StatsService { db, settings }
+    }
+
+    /// Reads the rollups only, so the numbers lag behind by up to the refresh interval.
+    async fn get_creator_stats(
+        &self,
+        user: &AuthenticatedUser,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<CreatorStats, AppError> {
+        let to = to.unwrap_or_else(|| Utc::now().date_naive());
+        let from = from.unwrap_or(to - Duration::days(DEFAULT_STATS_DAYS - 1));
+        if from > to {
+            return Err(AppError::BadRequest(ErrorCode::InvalidQuery, "from must not be after to".into()));
+        }
+        if (to - from).num_days() >= MAX_STATS_DAYS {
+            return Err(AppError::BadRequest(ErrorCode::InvalidQuery, format!(
+                "The range can cover at most {} days",
+                MAX_STATS_DAYS
+            )));
+        }
+
+        let stats = &self.db.stats;
+        let reels = self.db.reels.get_reel_titles_by_user_id(user.id).await?;
+        let rows = stats.get_daily_stats_by_user_id(user.id, from, to).await?;
+        let reel_viewers: HashMap<Uuid, i64> = stats.get_unique_viewers_by_reel(user.id, from, to).await?.into_iter().collect();
+        let day_viewers: HashMap<NaiveDate, i64> = stats.get_unique_viewers_by_day(user.id, from, to).await?.into_iter().collect();
+        let viewers = stats.get_unique_viewers(user.id, from, to).await?;
+        let refreshed_at = stats.get_last_refresh().await?;
+
+        let mut total = Tally::default();
+        let mut by_day: HashMap<NaiveDate, Tally> = HashMap::new();
+        let mut by_reel: HashMap<Uuid, (Tally, HashMap<NaiveDate, Tally>)> = HashMap::new();
+        for row in &rows {
+            total.add(row);
+            by_day.entry(row.day).or_default().add(row);
+            let (reel_total, reel_days) = by_reel.entry(row.reel_id).or_default();
+            reel_total.add(row);
+            reel_days.entry(row.day).or_default().add(row);
+        }
+
+        // viewers of several reels or days count once
+        total.unique_viewers = viewers;
+        for (day, tally) in by_day.iter_mut() {
+            tally.unique_viewers = day_viewers.get(day).copied().unwrap_or_default();
+        }
+        for (reel_id, (tally, _)) in by_reel.iter_mut() {
+            tally.unique_viewers = reel_viewers.get(reel_id).copied().unwrap_or_default();
+        }
+
+        let days: Vec<NaiveDate> = from.iter_days().take_while(|day| *day <= to).collect();
+        let reels = reels
+            .into_iter()
+            .map(|(reel_id, title)| {
+                let (tally, reel_days) = by_reel.remove(&reel_id).unwrap_or_default();
+                ReelStats {
+                    reel_id,
+                    title,
+                    totals: tally.counts(),
+                    daily: daily(&days, &reel_days),
+                }
+            })
+            .collect();
+
+        Ok(CreatorStats {
+            from,
+            to,
+            totals: total.counts(),
+            daily: daily(&days, &by_day),
+            reels,
+            refreshed_at,
+        })
+    }
+
+    async fn refresh_daily_stats(&self, since: Option<NaiveDate>) -> Result<u64, AppError> {
+        match self.db.stats.refresh_daily_stats(since).await {
+            Ok(written) => Ok(written),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl StatsService<'static> {
+    /// Periodically rebuilds the rollups of the last days. The first run after startup rebuilds every day,
+    /// which also fills in the days missed while the service was down.
+    pub fn start_refresher(self: Arc<Self>) {
+        let interval = std::time::Duration::from_secs(self.settings.refresh_interval_seconds.max(1));
+        tokio::spawn(async move {
+            let mut rebuilt = false;
+            loop {
+                let since = rebuilt
+                    .then(|| Utc::now().date_naive() - Duration::days(self.settings.lookback_days.max(0)));
+                match self.refresh_daily_stats(since).await {
+                    Ok(written) => {
+                        info!("Refreshed statistics of {} reel days", written);
+                        rebuilt = true;
+                    }
+                    Err(e) => error!("Failed to refresh reel statistics: {}", e),
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+}
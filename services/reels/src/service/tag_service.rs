@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::{
+    dao::database_context::Database, error::{error::AppError, problem::ErrorCode}, model::{PostReel, ReelPage, TagCount}, service::reel_service::{into_page, page_size, parse_cursor}
+};
+
+/// Longest tag kept, in characters.
+pub const MAX_TAG_LENGTH: usize = 64;
+/// Most tags a reel can carry, hashtags included.
+pub const MAX_TAGS_PER_REEL: usize = 20;
+/// Longest window trending tags are counted over.
+pub const MAX_TRENDING_HOURS: u32 = 24 * 30;
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Lowercases the tag and strips the leading `#`. Returns `None` for anything that is not a single word.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix('#').unwrap_or(tag).to_lowercase();
+    let length = tag.chars().count();
+    if length == 0 || length > MAX_TAG_LENGTH || !tag.chars().all(is_tag_char) {
+        return None;
+    }
+    Some(tag)
+}
+
+/// Words following a `#` that does not sit inside another word, so `C#` is not a hashtag.
+fn hashtags(text: &str) -> impl Iterator<Item = String> + '_ {
+    let mut previous = None;
+    text.char_indices().filter_map(move |(i, c)| {
+        let starts_tag = c == '#' && !previous.is_some_and(is_tag_char);
+        previous = Some(c);
+        if !starts_tag {
+            return None;
+        }
+        let rest = &text[i + 1..];
+        let end = rest.find(|c: char| !is_tag_char(c)).unwrap_or(rest.len());
+        normalize_tag(&rest[..end])
+    })
+}
+
+/// The tags of a reel: its explicit `tags` followed by the hashtags of its description, without repeats.
+/// Malformed hashtags are ignored, malformed explicit tags are rejected.
+pub fn reel_tags(reel: &PostReel) -> Result<Vec<String>, AppError> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in &reel.tags {
+        let tag = normalize_tag(tag).ok_or_else(|| {
+            AppError::BadRequest(ErrorCode::InvalidTag, format!(
+                "Tag {:?} must be one word of at most {} letters, digits or underscores",
+                tag, MAX_TAG_LENGTH
+            ))
+        })?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    for tag in hashtags(&reel.description) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    if tags.len() > MAX_TAGS_PER_REEL {
+        return Err(AppError::BadRequest(ErrorCode::InvalidTag, format!(
+            "A reel can have at most {} tags",
+            MAX_TAGS_PER_REEL
+        )));
+    }
+    Ok(tags)
+}
+
+#[async_trait]
+pub trait TagRepository<'a> {
+    fn new(db: Arc<Database<'a>>) -> Self;
+    async fn get_reels_by_tag(&self, tag: &str, cursor: Option<String>, limit: u32) -> Result<ReelPage, AppError>;
+    async fn autocomplete_tags(&self, prefix: &str, limit: u32) -> Result<Vec<TagCount>, AppError>;
+    async fn get_trending_tags(&self, hours: u32, limit: u32) -> Result<Vec<TagCount>, AppError>;
+}
+
+pub struct TagService<'a> {
+    pub db: Arc<Database<'a>>,
+}
+
+#[async_trait]
+impl<'a> TagRepository<'a> for TagService<'a> {
+    fn new(db: Arc<Database<'a>>) -> Self {
+        TagService { db }
+    }
+
+    async fn get_reels_by_tag(&self, tag: &str, cursor: Option<String>, limit: u32) -> Result<ReelPage, AppError> {
+        let cursor = parse_cursor(cursor)?;
+        let limit = page_size(limit);
+        let Some(tag) = normalize_tag(tag) else {
+            return Ok(ReelPage { reels: Vec::new(), next_cursor: None });
+        };
+
+        match self.db.tags.get_reels_by_tag_after_cursor(&tag, cursor.as_ref(), limit as i64 + 1).await {
+            Ok(reels) => Ok(into_page(reels, limit)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn autocomplete_tags(&self, prefix: &str, limit: u32) -> Result<Vec<TagCount>, AppError> {
+        let Some(prefix) = normalize_tag(prefix) else {
+            return Ok(Vec::new());
+        };
+        // `_` is a wildcard in LIKE patterns
+        let pattern = prefix.replace('_', "\\_");
+
+        match self.db.tags.get_tags_by_prefix(&pattern, page_size(limit) as i64).await {
+            Ok(tags) => Ok(tags),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_trending_tags(&self, hours: u32, limit: u32) -> Result<Vec<TagCount>, AppError> {
+        let hours = hours.clamp(1, MAX_TRENDING_HOURS) as i32;
+
+        match self.db.tags.get_trending_tags(hours, page_size(limit) as i64).await {
+            Ok(tags) => Ok(tags),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
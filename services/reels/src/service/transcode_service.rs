@@ -0,0 +1,151 @@
+use log::{error, info, warn};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use uuid::Uuid;
+
+use crate::{
+    config::{TranscodeSettings, UploadSettings}, dao::database_context::Database, error::error::AppError, media::{hls, MediaProbe, Transcoder}, model::{ProcessingStatus, Video}, storage::VideoStorage, util::stream_to_file::TempUpload
+};
+
+pub struct TranscodeService<'a> {
+    pub db: Arc<Database<'a>>,
+    pub storage: Arc<dyn VideoStorage>,
+    pub probe: Arc<dyn MediaProbe>,
+    pub transcoder: Arc<dyn Transcoder>,
+    pub upload: UploadSettings,
+    pub settings: TranscodeSettings,
+}
+
+/// Scratch directory for the renditions of one video, removed once they are uploaded.
+struct ScratchDir(PathBuf);
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.0)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("Failed to remove transcoding directory {}: {}", self.0.display(), e);
+        }
+    }
+}
+
+async fn list_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_dir() {
+                pending.push(entry.path());
+            } else {
+                files.push(entry.path());
+            }
+        }
+    }
+    Ok(files)
+}
+
+impl<'a> TranscodeService<'a> {
+    pub fn new(
+        db: Arc<Database<'a>>,
+        storage: Arc<dyn VideoStorage>,
+        probe: Arc<dyn MediaProbe>,
+        transcoder: Arc<dyn Transcoder>,
+        upload: UploadSettings,
+        settings: TranscodeSettings,
+    ) -> Self {
+        TranscodeService { db, storage, probe, transcoder, upload, settings }
+    }
+
+    async fn remove_prefix(&self, prefix: &str) {
+        if let Err(e) = self.storage.delete_prefix(prefix).await {
+            warn!("Failed to remove renditions under {}: {}", prefix, e);
+        }
+    }
+
+    /// Encodes every rendition of the stored file and uploads them with a master playlist under `prefix`.
+    async fn transcode(&self, video: &Video, storage_key: &str, prefix: &str) -> Result<(), AppError> {
+        let dir = Path::new(&self.upload.directory);
+        let source = TempUpload::reserve(dir, storage_key.to_string(), "application/octet-stream");
+        self.storage.get(storage_key, source.path()).await?;
+        let has_audio = self.probe.probe(source.path()).await?.has_audio;
+
+        let output = ScratchDir(dir.join(format!(".hls-{}", Uuid::new_v4())));
+        let renditions = hls::select_renditions(&self.settings.renditions, video.height);
+        for rendition in &renditions {
+            self.transcoder.transcode(source.path(), rendition, &output.0).await?;
+        }
+
+        let master = hls::master_playlist(&renditions, video.width.zip(video.height), has_audio);
+        tokio::fs::write(output.0.join(hls::MASTER_PLAYLIST), master)
+            .await
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+
+        let files = list_files(&output.0)
+            .await
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+        for file in files {
+            let relative = file
+                .strip_prefix(&output.0)
+                .map_err(|e| AppError::InternalError(e.to_string()))?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            self.storage
+                .put(&format!("{}/{}", prefix, relative), &file, hls::content_type(&file))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Transcodes the current file of a video. A failed attempt puts the video back to pending,
+    /// or marks it failed when no attempts are left.
+    pub async fn process(&self, video_id: Uuid, final_attempt: bool) -> Result<(), AppError> {
+        let video = match self.db.videos.start_transcode(video_id).await {
+            Ok(Some(video)) => video,
+            Ok(None) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let Some(storage_key) = video.storage_key.clone() else {
+            warn!("Video {} has no stored file to transcode, marked as failed", video_id);
+            return Ok(());
+        };
+
+        info!("Transcoding video {}", video_id);
+        // Every run writes to a fresh prefix so the current renditions keep serving until replaced.
+        let prefix = format!("hls/{}/{}", video_id, Uuid::new_v4());
+
+        if let Err(e) = self.transcode(&video, &storage_key, &prefix).await {
+            self.remove_prefix(&prefix).await;
+            let status = if final_attempt { ProcessingStatus::Failed } else { ProcessingStatus::Pending };
+            if let Err(e) = self.db.videos.set_processing_status(video_id, &storage_key, status).await {
+                error!("Failed to mark video {} as {}: {}", video_id, status, e);
+            }
+            return Err(e);
+        }
+
+        let stream_url = self.storage.url(&format!("{}/{}", prefix, hls::MASTER_PLAYLIST));
+        match self.db.videos.finish_transcode(video_id, &storage_key, &prefix, &stream_url).await {
+            Ok(Some(previous_prefix)) => {
+                if let Some(previous_prefix) = previous_prefix {
+                    self.remove_prefix(&previous_prefix).await;
+                }
+                info!("Video {} is ready to stream", video_id);
+                Ok(())
+            }
+            Ok(None) => {
+                info!("Video {} changed while transcoding, discarding renditions", video_id);
+                self.remove_prefix(&prefix).await;
+                Ok(())
+            }
+            Err(e) => {
+                self.remove_prefix(&prefix).await;
+                Err(e.into())
+            }
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{Duration as ChronoDuration, Utc};
+use log::{error, info, warn};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthenticatedUser, config::{TusSettings, UploadSettings}, dao::database_context::Database, error::{error::AppError, problem::ErrorCode}, model::{AuditOutcome, PostVideo, TusUpload}, service::{audit_service::AuditLog, video_service::{VideoRepository, VideoService}}, util::stream_to_file::TempUpload
+};
+
+#[async_trait]
+pub trait UploadRepository<'a> {
+    fn new(
+        db: Arc<Database<'a>>,
+        upload: UploadSettings,
+        tus: TusSettings,
+        videos: Arc<VideoService<'a>>,
+    ) -> Self;
+    async fn lock_upload(&self, upload_id: Uuid) -> Result<TusUpload, AppError>;
+    async fn unlock_upload(&self, upload_id: Uuid);
+    async fn get_upload(&self, upload_id: Uuid, user: &AuthenticatedUser) -> Result<TusUpload, AppError>;
+    async fn post_upload(&self, posting_user_id: Uuid, upload_length: i64, metadata: String) -> Result<TusUpload, AppError>;
+    async fn commit_upload(&self, upload: TusUpload, written: u64) -> Result<TusUpload, AppError>;
+    async fn delete_upload(&self, upload_id: Uuid, user: &AuthenticatedUser) -> Result<(), AppError>;
+    async fn remove_expired_uploads(&self) -> Result<usize, AppError>;
+}
+
+pub struct UploadService<'a> {
+    pub db: Arc<Database<'a>>,
+    pub upload: UploadSettings,
+    pub tus: TusSettings,
+    pub videos: Arc<VideoService<'a>>,
+    pub audit: AuditLog<'a>,
+}
+
+/// Decodes an Upload-Metadata header: comma separated keys, each followed by an optional base64 value.
+fn parse_metadata(header: &str) -> Result<HashMap<String, Option<String>>, AppError> {
+    let mut metadata = HashMap::new();
+    for pair in header.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+        let mut parts = pair.split(' ');
+        let key = parts.next().unwrap_or_default();
+        let value = match parts.next() {
+            Some(encoded) => {
+                let bytes = STANDARD
+                    .decode(encoded)
+                    .map_err(|_| AppError::BadRequest(ErrorCode::InvalidUploadMetadata, format!("Metadata value of {} is not valid base64", key)))?;
+                Some(
+                    String::from_utf8(bytes)
+                        .map_err(|_| AppError::BadRequest(ErrorCode::InvalidUploadMetadata, format!("Metadata value of {} is not valid UTF-8", key)))?,
+                )
+            }
+            None => None,
+        };
+        if parts.next().is_some() {
+            return Err(AppError::BadRequest(ErrorCode::InvalidUploadMetadata, format!("Invalid metadata pair: {}", pair)));
+        }
+        metadata.insert(key.to_string(), value);
+    }
+    Ok(metadata)
+}
+
+impl<'a> UploadService<'a> {
+    /// Where the received bytes of an upload are kept until it completes.
+    pub fn file_path(&self, upload_id: Uuid) -> PathBuf {
+        Path::new(&self.tus.directory).join(format!("{}.part", upload_id))
+    }
+
+    async fn remove_file(&self, upload_id: Uuid) {
+        let path = self.file_path(upload_id);
+        if let Err(e) = tokio::fs::remove_file(&path).await
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("Failed to remove upload file {}: {}", path.display(), e);
+        }
+    }
+
+    /// Creates the video from a completed upload. A file that is not a video ends the upload,
+    /// any other failure leaves it complete so an empty PATCH can try again.
+    async fn finish(&self, upload: TusUpload) -> Result<TusUpload, AppError> {
+        let video: PostVideo = serde_json::from_value(upload.video_metadata.clone())
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+
+        let part = self.file_path(upload.id);
+        let mut file = TempUpload::reserve(
+            Path::new(&self.upload.directory),
+            upload.file_name.clone(),
+            upload.content_type.as_deref().unwrap_or("application/octet-stream"),
+        );
+        file.size = upload.upload_length as u64;
+        if tokio::fs::hard_link(&part, file.path()).await.is_err() {
+            tokio::fs::copy(&part, file.path())
+                .await
+                .map_err(|e| AppError::InternalError(e.to_string()))?;
+        }
+
+        let video_id = match self.videos.post_video(video, upload.posting_user_id, file, None).await {
+            Ok(video) => video.id,
+            Err(AppError::BadRequest(code, msg)) => {
+                if let Err(e) = self.db.uploads.delete_upload(upload.id).await {
+                    error!("Failed to remove rejected upload {}: {}", upload.id, e);
+                }
+                self.remove_file(upload.id).await;
+                return Err(AppError::BadRequest(code, msg));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let upload = self
+            .db
+            .uploads
+            .finish_upload(upload.id, video_id)
+            .await?;
+        self.remove_file(upload.id).await;
+        info!("Upload {} completed as video {}", upload.id, video_id);
+        Ok(upload)
+    }
+}
+
+#[async_trait]
+impl<'a> UploadRepository<'a> for UploadService<'a> {
+    fn new(
+        db: Arc<Database<'a>>,
+        upload: UploadSettings,
+        tus: TusSettings,
+        videos: Arc<VideoService<'a>>,
+    ) -> Self {
+        let audit = AuditLog::new(&db);
+        UploadService { db, upload, tus, videos, audit }
+    }
+
+    /// Claims the upload for one PATCH and returns its current state. Offsets are only checked and moved,
+    /// and a completed upload only turned into a video, while it is claimed, so concurrent PATCH requests
+    /// cannot write the same bytes or create two videos. A claim never released lapses after `lock_seconds`.
+    async fn lock_upload(&self, upload_id: Uuid) -> Result<TusUpload, AppError> {
+        match self.db.uploads.claim_upload(upload_id, self.tus.lock_seconds).await {
+            Ok(Some(upload)) => Ok(upload),
+            Ok(None) => Err(AppError::Conflict(ErrorCode::UploadLocked, "Upload is being written by another request".into())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn unlock_upload(&self, upload_id: Uuid) {
+        if let Err(e) = self.db.uploads.release_upload(upload_id).await {
+            warn!("Failed to release upload {}: {}", upload_id, e);
+        }
+    }
+
+    /// Uploads are private to the user who started them, moderators included.
+    async fn get_upload(&self, upload_id: Uuid, user: &AuthenticatedUser) -> Result<TusUpload, AppError> {
+        let upload = match self.db.uploads.get_upload_by_id(upload_id).await {
+            Ok(upload) => upload,
+            Err(sqlx::Error::RowNotFound) => return Err(AppError::NotFound(ErrorCode::UploadNotFound, "Upload not found".into())),
+            Err(e) => return Err(e.into()),
+        };
+
+        if upload.posting_user_id != user.id {
+            self.audit
+                .record(user, "upload.access", Some(upload.id), Some(upload.posting_user_id), AuditOutcome::Denied)
+                .await;
+            return Err(AppError::Forbidden(ErrorCode::UploadForbidden, "Only the posting user can access this upload".into()));
+        }
+        Ok(upload)
+    }
+
+    async fn post_upload(&self, posting_user_id: Uuid, upload_length: i64, metadata: String) -> Result<TusUpload, AppError> {
+        let max_size = self.upload.max_file_size_bytes();
+        if upload_length <= 0 {
+            return Err(AppError::BadRequest(ErrorCode::InvalidUploadHeader, "Upload-Length must be a positive number".into()));
+        }
+        if upload_length as u64 > max_size {
+            return Err(AppError::PayloadTooLarge(ErrorCode::UploadTooLarge, format!(
+                "File exceeds the maximum upload size of {} bytes",
+                max_size
+            )));
+        }
+
+        let values = parse_metadata(&metadata)?;
+        let file_name = values
+            .get("filename")
+            .cloned()
+            .flatten()
+            .ok_or_else(|| AppError::BadRequest(ErrorCode::InvalidUploadMetadata, "Missing filename in Upload-Metadata".into()))?;
+        let content_type = values.get("filetype").cloned().flatten();
+        let video = values
+            .get("video")
+            .cloned()
+            .flatten()
+            .ok_or_else(|| AppError::BadRequest(ErrorCode::InvalidUploadMetadata, "Missing video in Upload-Metadata".into()))?;
+        let video: PostVideo = serde_json::from_str(&video)
+            .map_err(|_| AppError::BadRequest(ErrorCode::InvalidUploadMetadata, "Invalid JSON for video metadata".into()))?;
+
+        let upload = TusUpload {
+            id: Uuid::new_v4(),
+            posting_user_id,
+            upload_length,
+            upload_offset: 0,
+            metadata,
+            file_name,
+            content_type,
+            video_metadata: serde_json::to_value(&video).map_err(|e| AppError::InternalError(e.to_string()))?,
+            video_id: None,
+            created_at: Utc::now(),
+            expires_at: Utc::now() + ChronoDuration::hours(self.tus.expiration_hours),
+        };
+
+        let path = self.file_path(upload.id);
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir)
+                .await
+                .map_err(|e| AppError::InternalError(e.to_string()))?;
+        }
+        tokio::fs::File::create(&path)
+            .await
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+
+        match self.db.uploads.post_upload(&upload).await {
+            Ok(upload) => Ok(upload),
+            Err(e) => {
+                self.remove_file(upload.id).await;
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn commit_upload(&self, upload: TusUpload, written: u64) -> Result<TusUpload, AppError> {
+        let upload = if written > 0 {
+            match self
+                .db
+                .uploads
+                .advance_upload(
+                    upload.id,
+                    upload.upload_offset,
+                    upload.upload_offset + written as i64,
+                    self.tus.expiration_hours,
+                )
+                .await
+            {
+                Ok(Some(upload)) => upload,
+                Ok(None) => return Err(AppError::Conflict(ErrorCode::UploadOffsetMismatch, "Upload was modified by another request".into())),
+                Err(e) => return Err(e.into()),
+            }
+        } else {
+            upload
+        };
+
+        if upload.is_complete() && upload.video_id.is_none() {
+            return self.finish(upload).await;
+        }
+        Ok(upload)
+    }
+
+    async fn delete_upload(&self, upload_id: Uuid, user: &AuthenticatedUser) -> Result<(), AppError> {
+        self.get_upload(upload_id, user).await?;
+
+        match self.db.uploads.delete_upload(upload_id).await {
+            Ok(_) => {
+                self.remove_file(upload_id).await;
+                Ok(())
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn remove_expired_uploads(&self) -> Result<usize, AppError> {
+        let expired = self
+            .db
+            .uploads
+            .delete_expired_uploads()
+            .await?;
+
+        for upload in &expired {
+            self.remove_file(upload.id).await;
+        }
+        Ok(expired.len())
+    }
+}
+
+impl UploadService<'static> {
+    /// Periodically removes expired uploads along with any bytes received for them.
+    pub fn start_sweeper(self: Arc<Self>) {
+        let interval = Duration::from_secs(self.tus.sweep_interval_seconds.max(1));
+        tokio::spawn(async move {
+            loop {
+                match self.remove_expired_uploads().await {
+                    Ok(0) => {}
+                    Ok(removed) => info!("Removed {} expired uploads", removed),
+                    Err(e) => error!("Failed to remove expired uploads: {}", e),
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_code(header: &str) -> ErrorCode {
+        match parse_metadata(header) {
+            Err(AppError::BadRequest(code, _)) => code,
+            other => panic!("expected a bad request, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn decodes_keys_with_and_without_values() {
+        let metadata = parse_metadata("filename bXkgdmlkZW8ubXA0, is_confidential ,filetype dmlkZW8vbXA0").unwrap();
+
+        assert_eq!(metadata.len(), 3);
+        assert_eq!(metadata["filename"].as_deref(), Some("my video.mp4"));
+        assert_eq!(metadata["filetype"].as_deref(), Some("video/mp4"));
+        assert_eq!(metadata["is_confidential"], None);
+    }
+
+    #[test]
+    fn accepts_an_empty_header() {
+        assert!(parse_metadata("").unwrap().is_empty());
+        assert!(parse_metadata(" , ").unwrap().is_empty());
+    }
+
+    #[test]
+    fn decodes_utf8_values() {
+        let encoded = STANDARD.encode("przepis na żurek.mp4");
+        let metadata = parse_metadata(&format!("filename {}", encoded)).unwrap();
+        assert_eq!(metadata["filename"].as_deref(), Some("przepis na żurek.mp4"));
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert_eq!(error_code("filename not*base64"), ErrorCode::InvalidUploadMetadata);
+        assert_eq!(error_code(&format!("filename {}", STANDARD.encode([0xFF, 0xFE]))), ErrorCode::InvalidUploadMetadata);
+        assert_eq!(error_code("filename bXk= extra"), ErrorCode::InvalidUploadMetadata);
+    }
+}
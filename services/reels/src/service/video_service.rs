@@ -1,115 +1,488 @@
 use async_trait::async_trait;
-use std::{path::PathBuf, sync::Arc};
-use tokio::{fs::{self, File}, io::AsyncWriteExt};
+use log::{error, warn};
+use std::{path::Path, sync::Arc};
 use uuid::Uuid;
-use bytes::BytesMut;
 
 use crate::{
-    dao::database_context::Database, error::error::AppError, model::{PostVideo, Video}
+    auth::AuthenticatedUser, config::{MediaSettings, UploadSettings}, dao::database_context::Database, error::{error::AppError, problem::ErrorCode}, media::{Artwork, FrameExtractor, MediaProbe, ProbeError}, model::{MediaTask, PostVideo, PosterFrame, ProcessingStatus, RecipeSummary, Video}, service::{audit_service::AuditLog, job_service::JobQueue}, storage::VideoStorage, util::stream_to_file::TempUpload
 };
 
 #[async_trait]
 pub trait VideoRepository<'a> {
-    fn new(db: Arc<Database<'a>>) -> Self;
+    fn new(
+        db: Arc<Database<'a>>,
+        upload: UploadSettings,
+        media: MediaSettings,
+        storage: Arc<dyn VideoStorage>,
+        probe: Arc<dyn MediaProbe>,
+        frames: Arc<dyn FrameExtractor>,
+        jobs: JobQueue<'a>,
+    ) -> Self;
     async fn get_video_by_id(&self, video_id: Uuid) -> Result<Video, AppError>;
-    async fn get_video_by_reel_id(&self, reel_id: Uuid) -> Result<Video, AppError>;    async fn post_video(
+    async fn get_video_by_reel_id(&self, reel_id: Uuid) -> Result<Video, AppError>;
+    async fn post_video(
         &self,
         video: PostVideo,
         posting_user_id: Uuid,
-        temp_file: BytesMut,
-        file_name: String,
-    ) -> Result<Uuid, AppError>;
-    async fn put_video(&self, video_id: Uuid, video: PostVideo, posting_user_id: Uuid) -> Result<(), AppError>;
-    async fn delete_video(&self, video_id: Uuid) -> Result<(), AppError>;
+        upload: TempUpload,
+        cover: Option<TempUpload>,
+    ) -> Result<Video, AppError>;
+    async fn put_video(&self, video_id: Uuid, video: PostVideo, user: &AuthenticatedUser) -> Result<Video, AppError>;
+    async fn put_video_file(
+        &self,
+        video_id: Uuid,
+        video: Option<PostVideo>,
+        user: &AuthenticatedUser,
+        upload: TempUpload,
+        cover: Option<TempUpload>,
+    ) -> Result<Video, AppError>;
+    async fn put_poster_frame(
+        &self,
+        video_id: Uuid,
+        frame: PosterFrame,
+        user: &AuthenticatedUser,
+    ) -> Result<Video, AppError>;
+    async fn put_cover(&self, video_id: Uuid, cover: TempUpload, user: &AuthenticatedUser) -> Result<Video, AppError>;
+    async fn delete_video(&self, video_id: Uuid, user: &AuthenticatedUser) -> Result<Video, AppError>;
+    async fn get_videos_by_recipe_id(&self, recipe_id: Uuid) -> Result<Vec<Video>, AppError>;
+    async fn get_recipes_by_video_id(&self, video_id: Uuid) -> Result<Vec<RecipeSummary>, AppError>;
+    async fn attach_recipe(&self, video_id: Uuid, recipe_id: Uuid, user: &AuthenticatedUser) -> Result<(), AppError>;
+    async fn detach_recipe(&self, video_id: Uuid, recipe_id: Uuid, user: &AuthenticatedUser) -> Result<(), AppError>;
 }
 
 pub struct VideoService<'a> {
     pub db: Arc<Database<'a>>,
+    pub upload: UploadSettings,
+    pub media: MediaSettings,
+    pub storage: Arc<dyn VideoStorage>,
+    pub probe: Arc<dyn MediaProbe>,
+    pub frames: Arc<dyn FrameExtractor>,
+    pub jobs: JobQueue<'a>,
+    pub audit: AuditLog<'a>,
+}
+
+/// A video whose files are in storage but whose row has not been written yet.
+pub struct StagedVideo {
+    pub video: Video,
+    poster_offset: Option<f64>,
+}
+
+/// Where a poster is taken from: a frame of a video, or an image uploaded by the creator.
+enum ArtworkSource<'p> {
+    Frame(&'p Path, f64),
+    Cover(&'p Path),
+}
+
+impl VideoService<'_> {
+    async fn store_upload(&self, file_id: Uuid, upload: &TempUpload) -> Result<String, AppError> {
+        let key = format!("{}{}", file_id, upload.extension());
+
+        self.storage.put(&key, upload.path(), &upload.content_type).await?;
+
+        Ok(key)
+    }
+
+    async fn remove_file(&self, storage_key: &str) {
+        if let Err(e) = self.storage.delete_if_exists(storage_key).await {
+            warn!("Failed to remove video file {}: {}", storage_key, e);
+        }
+    }
+
+    /// Queues follow-up work for a stored file. The upload already succeeded, so a failure is only logged;
+    /// videos left pending are picked up again when the workers start.
+    async fn schedule(&self, task: MediaTask) {
+        if let Err(e) = self.jobs.enqueue(&task).await {
+            error!("Failed to schedule {} job: {}", task.kind(), e);
+        }
+    }
+
+    /// Queues the transcode and, unless the creator sent a cover, the poster of a newly stored file.
+    async fn schedule_processing(&self, video_id: Uuid, has_cover: bool, poster_offset: Option<f64>) {
+        self.schedule(MediaTask::Transcode { video_id }).await;
+        if !has_cover {
+            self.schedule(MediaTask::Thumbnail { video_id, offset_seconds: poster_offset }).await;
+        }
+    }
+
+    async fn remove_artwork(&self, artwork: Option<&Artwork>) {
+        for key in artwork.iter().flat_map(|artwork| artwork.keys()) {
+            self.remove_file(key).await;
+        }
+    }
+
+    /// Loads the video if the user posted it or is a moderator.
+    async fn check_owner(&self, video_id: Uuid, user: &AuthenticatedUser, action: &str) -> Result<Video, AppError> {
+        match self.db.videos.get_video_by_id(video_id).await {
+            Ok(video) => {
+                if !self.audit.authorize_owner(user, action, video.id, video.posting_user_id).await {
+                    return Err(AppError::Forbidden(ErrorCode::VideoForbidden, "Only the posting user can change this video".into()));
+                }
+                Ok(video)
+            }
+            Err(sqlx::Error::RowNotFound) => Err(AppError::NotFound(ErrorCode::VideoNotFound, "Video not found".into())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn check_recipe(&self, recipe_id: Uuid) -> Result<(), AppError> {
+        match self.db.recipe_videos.recipe_exists(recipe_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(AppError::NotFound(ErrorCode::RecipeNotFound, "Recipe not found".into())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Resolves the poster timestamp. The configured default is moved to the middle of clips shorter
+    /// than it, while an explicitly requested offset past the end is rejected.
+    fn poster_offset(&self, requested: Option<f64>, duration_seconds: Option<f64>) -> Result<f64, AppError> {
+        let offset = requested.unwrap_or(self.media.poster_offset_seconds);
+        if !offset.is_finite() || offset < 0.0 {
+            return Err(AppError::BadRequest(ErrorCode::InvalidPosterOffset, "Poster offset must be a non-negative number of seconds".into()));
+        }
+
+        match duration_seconds.filter(|d| *d > 0.0) {
+            Some(duration) if requested.is_some() && offset > duration => {
+                Err(AppError::BadRequest(ErrorCode::InvalidPosterOffset, "Poster offset is past the end of the video".into()))
+            }
+            Some(duration) if offset >= duration => Ok(duration / 2.0),
+            _ => Ok(offset),
+        }
+    }
+
+    /// Renders a poster and a thumbnail scaled down from it, and stores both.
+    async fn store_artwork(&self, source: ArtworkSource<'_>) -> Result<Artwork, AppError> {
+        let format = self.media.poster_format;
+        let dir = Path::new(&self.upload.directory);
+        let name = format!("{}{}", Uuid::new_v4(), format.extension());
+        let poster = TempUpload::reserve(dir, name.clone(), format.content_type());
+        let thumbnail = TempUpload::reserve(dir, name.clone(), format.content_type());
+
+        let (path, offset) = match source {
+            ArtworkSource::Frame(path, offset) => (path, Some(offset)),
+            ArtworkSource::Cover(path) => (path, None),
+        };
+        self.frames
+            .extract_frame(path, offset, self.media.poster_width, poster.path())
+            .await
+            .map_err(|e| match (e, offset) {
+                (ProbeError::NotVideo(msg), None) => {
+                    AppError::BadRequest(ErrorCode::InvalidCover, format!("Cover is not a readable image: {}", msg))
+                }
+                (e, _) => e.into(),
+            })?;
+        self.frames
+            .extract_frame(poster.path(), None, Some(self.media.thumbnail_width), thumbnail.path())
+            .await?;
+
+        let artwork = Artwork {
+            poster_url: self.storage.url(&format!("posters/{}", name)),
+            poster_key: format!("posters/{}", name),
+            thumbnail_url: self.storage.url(&format!("thumbnails/{}", name)),
+            thumbnail_key: format!("thumbnails/{}", name),
+        };
+
+        self.storage.put(&artwork.poster_key, poster.path(), format.content_type()).await?;
+        if let Err(e) = self.storage.put(&artwork.thumbnail_key, thumbnail.path(), format.content_type()).await {
+            self.remove_file(&artwork.poster_key).await;
+            return Err(e.into());
+        }
+
+        Ok(artwork)
+    }
+
+    async fn cover_artwork(&self, cover: Option<&TempUpload>) -> Result<Option<Artwork>, AppError> {
+        match cover {
+            Some(cover) => self.store_artwork(ArtworkSource::Cover(cover.path())).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Points the video at new artwork and removes the images it replaces.
+    async fn replace_artwork(&self, video_id: Uuid, artwork: Artwork) -> Result<Video, AppError> {
+        match self.db.videos.put_video_artwork(video_id, &artwork).await {
+            Ok((video, old_keys)) => {
+                self.jobs.cleanup(old_keys, Vec::new()).await;
+                Ok(video)
+            }
+            Err(e) => {
+                self.remove_artwork(Some(&artwork)).await;
+                match e {
+                    sqlx::Error::RowNotFound => Err(AppError::NotFound(ErrorCode::VideoNotFound, "Video not found".into())),
+                    e => Err(e.into()),
+                }
+            }
+        }
+    }
+
+    /// Probes an upload and puts it and its cover into storage, without writing the video row.
+    /// The caller inserts `StagedVideo::video`, then either publishes or discards it.
+    pub async fn stage_video(
+        &self,
+        video: PostVideo,
+        posting_user_id: Uuid,
+        upload: &TempUpload,
+        cover: Option<&TempUpload>,
+    ) -> Result<StagedVideo, AppError> {
+        let media = self.probe.probe(upload.path()).await?;
+        let poster_offset = video.poster_offset_seconds;
+        self.poster_offset(poster_offset, media.duration_seconds)?;
+
+        let artwork = self.cover_artwork(cover).await?;
+        let video_id = Uuid::new_v4();
+        let storage_key = match self.store_upload(video_id, upload).await {
+            Ok(storage_key) => storage_key,
+            Err(e) => {
+                self.remove_artwork(artwork.as_ref()).await;
+                return Err(e);
+            }
+        };
+
+        let video: Video = Video {
+            id: video_id,
+            posting_user_id,
+            description: video.description,
+            title: video.title,
+            video_length_seconds: media.length_seconds().or(video.video_length_seconds).unwrap_or(0),
+            video_url: self.storage.url(&storage_key),
+            width: media.width,
+            height: media.height,
+            codec: media.codec,
+            bitrate: media.bitrate,
+            container_format: Some(media.container_format),
+            poster_url: artwork.as_ref().map(|a| a.poster_url.clone()),
+            thumbnail_url: artwork.as_ref().map(|a| a.thumbnail_url.clone()),
+            processing_status: ProcessingStatus::Pending,
+            stream_url: None,
+            storage_key: Some(storage_key),
+            poster_key: artwork.as_ref().map(|a| a.poster_key.clone()),
+            thumbnail_key: artwork.as_ref().map(|a| a.thumbnail_key.clone()),
+            hls_prefix: None,
+        };
+
+        Ok(StagedVideo { video, poster_offset })
+    }
+
+    /// Queues processing once the row of a staged video is committed.
+    pub async fn publish_staged(&self, staged: &StagedVideo) {
+        self.schedule_processing(staged.video.id, staged.video.poster_key.is_some(), staged.poster_offset)
+            .await;
+    }
+
+    /// Removes the stored files of a staged video whose row could not be committed.
+    pub async fn discard_staged(&self, staged: &StagedVideo) {
+        for key in staged.video.storage_keys() {
+            self.remove_file(key).await;
+        }
+    }
+
+    /// Replaces the poster with a frame of the stored video, at the configured offset when none is given.
+    pub async fn regenerate_poster(&self, video_id: Uuid, offset_seconds: Option<f64>) -> Result<Video, AppError> {
+        let video = match self.db.videos.get_video_by_id(video_id).await {
+            Ok(video) => video,
+            Err(sqlx::Error::RowNotFound) => return Err(AppError::NotFound(ErrorCode::VideoNotFound, "Video not found".into())),
+            Err(e) => return Err(e.into()),
+        };
+        let storage_key = video
+            .storage_key
+            .ok_or_else(|| AppError::NotFound(ErrorCode::VideoNotFound, "Video file not found".into()))?;
+        let duration = (video.video_length_seconds > 0).then_some(video.video_length_seconds as f64);
+        let offset = self.poster_offset(offset_seconds, duration)?;
+
+        let source = TempUpload::reserve(
+            Path::new(&self.upload.directory),
+            storage_key.clone(),
+            "application/octet-stream",
+        );
+        self.storage.get(&storage_key, source.path()).await?;
+
+        let artwork = self.store_artwork(ArtworkSource::Frame(source.path(), offset)).await?;
+        self.replace_artwork(video_id, artwork).await
+    }
 }
 
 #[async_trait]
 impl<'a> VideoRepository<'a> for VideoService<'a> {
-    fn new(db: Arc<Database<'a>>) -> Self {
-        VideoService { db }
+    fn new(
+        db: Arc<Database<'a>>,
+        upload: UploadSettings,
+        media: MediaSettings,
+        storage: Arc<dyn VideoStorage>,
+        probe: Arc<dyn MediaProbe>,
+        frames: Arc<dyn FrameExtractor>,
+        jobs: JobQueue<'a>,
+    ) -> Self {
+        let audit = AuditLog::new(&db);
+        VideoService { db, upload, media, storage, probe, frames, jobs, audit }
     }
 
     async fn get_video_by_id(&self, video_id: Uuid) -> Result<Video, AppError> {
         match self.db.videos.get_video_by_id(video_id).await {
             Ok(video) => Ok(video),
-            Err(e) => Err(AppError::InternalError(e.to_string())),
+            Err(sqlx::Error::RowNotFound) => Err(AppError::NotFound(ErrorCode::VideoNotFound, "Video not found".into())),
+            Err(e) => Err(e.into()),
         }
     }
 
     async fn get_video_by_reel_id(&self, reel_id: Uuid) -> Result<Video, AppError> {
         match self.db.videos.get_video_by_reel_id(reel_id).await {
             Ok(video) => Ok(video),
-            Err(e) => Err(AppError::InternalError(e.to_string())),
+            Err(sqlx::Error::RowNotFound) => Err(AppError::NotFound(ErrorCode::VideoNotFound, "Reel or video not found".into())),
+            Err(e) => Err(e.into()),
         }
-    }    async fn post_video(
+    }
+
+    async fn post_video(
         &self,
         video: PostVideo,
         posting_user_id: Uuid,
-        file_bytes: BytesMut,
-        file_name: String,
-    ) -> Result<Uuid, AppError> {
-        let extension = PathBuf::from(&file_name)
-            .extension()
-            .and_then(std::ffi::OsStr::to_str)
-            .map_or(String::new(), |ext| format!(".{}", ext));
+        upload: TempUpload,
+        cover: Option<TempUpload>,
+    ) -> Result<Video, AppError> {
+        let staged = self.stage_video(video, posting_user_id, &upload, cover.as_ref()).await?;
 
-        let video_id = Uuid::new_v4();
-        let unique_filename = format!("{}{}", video_id, extension);
-        let dir = PathBuf::from("./upload");
+        match self.db.videos.post_video(&staged.video).await {
+            Ok(video) => {
+                self.publish_staged(&staged).await;
+                Ok(video)
+            }
+            Err(e) => {
+                self.discard_staged(&staged).await;
+                Err(e.into())
+            }
+        }
+    }
 
-        std::fs::create_dir_all(&dir).map_err(|e| AppError::InternalError(e.to_string()))?;
+    async fn put_video(&self, video_id: Uuid, video: PostVideo, user: &AuthenticatedUser) -> Result<Video, AppError> {
+        self.check_owner(video_id, user, "video.update").await?;
 
-        let path = dir.join(&unique_filename);
-        
-        let mut file = File::create(&path).await.map_err(|e| AppError::InternalError(e.to_string()))?;
-        file.write_all(&file_bytes).await.map_err(|e| AppError::InternalError(e.to_string()))?;
+        match self.db.videos.put_video(video_id, &video).await {
+            Ok(video) => Ok(video),
+            Err(sqlx::Error::RowNotFound) => Err(AppError::NotFound(ErrorCode::VideoNotFound, "Video not found".into())),
+            Err(e) => Err(e.into()),
+        }
+    }
 
-        let video_url = format!("/static/videos/{}", unique_filename);        let video: Video = Video {
-            id: video_id,
-            posting_user_id: posting_user_id,
-            description: video.description,
-            title: video.title,
-            video_length_seconds: video.video_length_seconds,
-            video_url: video_url,
+    async fn put_video_file(
+        &self,
+        video_id: Uuid,
+        video: Option<PostVideo>,
+        user: &AuthenticatedUser,
+        upload: TempUpload,
+        cover: Option<TempUpload>,
+    ) -> Result<Video, AppError> {
+        self.check_owner(video_id, user, "video.replace_file").await?;
+        let media = self.probe.probe(upload.path()).await?;
+        let poster_offset = video.as_ref().and_then(|v| v.poster_offset_seconds);
+        self.poster_offset(poster_offset, media.duration_seconds)?;
+
+        // The replacement gets a fresh name so the old file keeps serving until the row is committed.
+        let artwork = self.cover_artwork(cover.as_ref()).await?;
+        let storage_key = match self.store_upload(Uuid::new_v4(), &upload).await {
+            Ok(storage_key) => storage_key,
+            Err(e) => {
+                self.remove_artwork(artwork.as_ref()).await;
+                return Err(e);
+            }
+        };
+        let video_url = self.storage.url(&storage_key);
+
+        let updated = self
+            .db
+            .videos
+            .put_video_file(video_id, video.as_ref(), &video_url, &storage_key, &media)
+            .await;
+        let updated = match updated {
+            Ok((video, previous)) => {
+                self.jobs
+                    .cleanup(previous.storage_key.into_iter().collect(), previous.hls_prefix.into_iter().collect())
+                    .await;
+                self.schedule_processing(video_id, artwork.is_some(), poster_offset).await;
+                video
+            }
+            Err(e) => {
+                self.remove_file(&storage_key).await;
+                self.remove_artwork(artwork.as_ref()).await;
+                return match e {
+                    sqlx::Error::RowNotFound => Err(AppError::NotFound(ErrorCode::VideoNotFound, "Video not found".into())),
+                    e => Err(e.into()),
+                };
+            }
         };
 
-        match self.db.videos.post_video(&video).await {
-            Ok(video_id) => Ok(video_id),
-            Err(e) => Err(AppError::InternalError(e.to_string())),
+        match artwork {
+            Some(artwork) => self.replace_artwork(video_id, artwork).await,
+            None => Ok(updated),
         }
     }
 
-    async fn put_video(&self, video_id: Uuid, video: PostVideo, posting_user_id: Uuid) -> Result<(), AppError> {
-        todo!();
-        // let video: Video = Video {
-        //     id: video_id,
-        //     posting_user_id: video.posting_user_id,
-        //     description: video.description.clone(),
-        //     title: video.description.clone(),
-        //     video_length_seconds: video.video_length_seconds,
-        //     video_url: "placeholder".to_string(),
-        // };
-        
-        // match self.db.videos.put_video(video).await {
-        //     Ok(_) => Ok(()),
-        //     Err(e) => Err(AppError::InternalError(e.to_string())),
-        // }
+    async fn put_poster_frame(
+        &self,
+        video_id: Uuid,
+        frame: PosterFrame,
+        user: &AuthenticatedUser,
+    ) -> Result<Video, AppError> {
+        self.check_owner(video_id, user, "video.poster_frame").await?;
+
+        self.regenerate_poster(video_id, Some(frame.offset_seconds)).await
+    }
+
+    async fn put_cover(&self, video_id: Uuid, cover: TempUpload, user: &AuthenticatedUser) -> Result<Video, AppError> {
+        self.check_owner(video_id, user, "video.cover").await?;
+
+        let artwork = self.store_artwork(ArtworkSource::Cover(cover.path())).await?;
+        self.replace_artwork(video_id, artwork).await
     }
 
-    async fn delete_video(&self, video_id: Uuid) -> Result<(), AppError> {
+    async fn delete_video(&self, video_id: Uuid, user: &AuthenticatedUser) -> Result<Video, AppError> {
+        self.check_owner(video_id, user, "video.delete").await?;
+
         match self.db.videos.delete_video(video_id).await {
-            Ok(Some(video_url)) => {
-                if let Err(e) = fs::remove_file(&video_url).await {
-                    return Err(AppError::InternalError(e.to_string()))
-                }
-                Ok(())
+            Ok(Some(video)) => {
+                self.jobs
+                    .cleanup(video.storage_keys().map(str::to_string).collect(), video.hls_prefix.clone().into_iter().collect())
+                    .await;
+                Ok(video)
             }
-            Ok(None) => Err(AppError::InternalError("Video not found".into())),
-            Err(e) => Err(AppError::InternalError(e.to_string())),
+            Ok(None) => Err(AppError::NotFound(ErrorCode::VideoNotFound, "Video not found".into())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_videos_by_recipe_id(&self, recipe_id: Uuid) -> Result<Vec<Video>, AppError> {
+        self.check_recipe(recipe_id).await?;
+
+        match self.db.recipe_videos.get_videos_by_recipe_id(recipe_id).await {
+            Ok(videos) => Ok(videos),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_recipes_by_video_id(&self, video_id: Uuid) -> Result<Vec<RecipeSummary>, AppError> {
+        self.get_video_by_id(video_id).await?;
+
+        match self.db.recipe_videos.get_recipes_by_video_id(video_id).await {
+            Ok(recipes) => Ok(recipes),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn attach_recipe(&self, video_id: Uuid, recipe_id: Uuid, user: &AuthenticatedUser) -> Result<(), AppError> {
+        self.check_owner(video_id, user, "video.attach_recipe").await?;
+        self.check_recipe(recipe_id).await?;
+
+        match self.db.recipe_videos.post_recipe_video(recipe_id, video_id).await {
+            Ok(()) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn detach_recipe(&self, video_id: Uuid, recipe_id: Uuid, user: &AuthenticatedUser) -> Result<(), AppError> {
+        self.check_owner(video_id, user, "video.detach_recipe").await?;
+
+        match self.db.recipe_videos.delete_recipe_video(recipe_id, video_id).await {
+            Ok(0) => Err(AppError::NotFound(ErrorCode::ResourceNotFound, "Video is not linked to this recipe".into())),
+            Ok(_) => Ok(()),
+            Err(e) => Err(e.into()),
         }
     }
 }
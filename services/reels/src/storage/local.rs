@@ -0,0 +1,73 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use tokio::fs;
+
+use crate::config::LocalStorageSettings;
+
+use super::{prefix_path, StorageError, VideoStorage};
+
+pub struct LocalStorage {
+    root: PathBuf,
+    public_url: String,
+}
+
+impl LocalStorage {
+    pub fn new(settings: &LocalStorageSettings) -> Self {
+        LocalStorage {
+            root: PathBuf::from(&settings.directory),
+            public_url: settings.public_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key.trim_start_matches('/'))
+    }
+}
+
+fn not_found(key: &str, e: std::io::Error) -> StorageError {
+    match e.kind() {
+        std::io::ErrorKind::NotFound => StorageError::NotFound(key.to_string()),
+        _ => StorageError::Io(e),
+    }
+}
+
+#[async_trait]
+impl VideoStorage for LocalStorage {
+    async fn put(&self, key: &str, source: &Path, _content_type: &str) -> Result<(), StorageError> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        // Uploads are staged on the same volume, so a hard link avoids copying the whole file.
+        if fs::hard_link(source, &path).await.is_err() {
+            fs::copy(source, &path).await?;
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str, destination: &Path) -> Result<(), StorageError> {
+        fs::copy(self.path(key), destination)
+            .await
+            .map(|_| ())
+            .map_err(|e| not_found(key, e))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StorageError> {
+        fs::remove_file(self.path(key))
+            .await
+            .map_err(|e| not_found(key, e))
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), StorageError> {
+        match fs::remove_dir_all(self.root.join(prefix_path(prefix)?)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(StorageError::Io(e)),
+            _ => Ok(()),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.public_url, key.trim_start_matches('/'))
+    }
+}
@@ -0,0 +1,91 @@
+use std::{path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use log::warn;
+use thiserror::Error;
+
+use crate::{config::StorageSettings, error::error::AppError};
+
+pub mod local;
+pub mod s3;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("Object not found: {0}")]
+    NotFound(String),
+    #[error("Storage I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Storage backend error: {0}")]
+    Backend(String),
+    #[error("Invalid storage prefix: {0:?}")]
+    InvalidPrefix(String),
+}
+
+impl From<StorageError> for AppError {
+    fn from(e: StorageError) -> Self {
+        match e {
+            StorageError::Backend(_) => AppError::Unavailable(e.to_string()),
+            e => AppError::InternalError(e.to_string()),
+        }
+    }
+}
+
+/// Object storage for uploaded media. Keys are relative paths such as `<video_id>.mp4`.
+#[async_trait]
+pub trait VideoStorage: Send + Sync {
+    /// Stores the content of a local file under `key`. The source file is left in place.
+    async fn put(&self, key: &str, source: &Path, content_type: &str) -> Result<(), StorageError>;
+    /// Copies the object stored under `key` into a local file.
+    async fn get(&self, key: &str, destination: &Path) -> Result<(), StorageError>;
+    async fn delete(&self, key: &str) -> Result<(), StorageError>;
+    /// Deletes every object under the `prefix` directory. A prefix with an empty, `.` or `..`
+    /// segment is rejected, so a bad key can never wipe the whole store.
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), StorageError>;
+    /// Public URL clients use to fetch the object.
+    fn url(&self, key: &str) -> String;
+
+    /// Deletes the object, logging instead of failing when it is already gone.
+    async fn delete_if_exists(&self, key: &str) -> Result<(), StorageError> {
+        match self.delete(key).await {
+            Err(StorageError::NotFound(key)) => {
+                warn!("Stored object {} was already missing", key);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+}
+
+/// Checks a `delete_prefix` argument and returns it without surrounding slashes.
+fn prefix_path(prefix: &str) -> Result<&str, StorageError> {
+    let path = prefix.trim_matches('/');
+    if path.split('/').any(|segment| matches!(segment, "" | "." | "..")) {
+        return Err(StorageError::InvalidPrefix(prefix.to_string()));
+    }
+    Ok(path)
+}
+
+pub fn from_settings(settings: &StorageSettings) -> Arc<dyn VideoStorage> {
+    match settings {
+        StorageSettings::Local(local) => Arc::new(local::LocalStorage::new(local)),
+        StorageSettings::S3(s3) => Arc::new(s3::S3Storage::new(s3)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_path_trims_slashes() {
+        assert_eq!(prefix_path("hls/abc/def").unwrap(), "hls/abc/def");
+        assert_eq!(prefix_path("/hls/abc/").unwrap(), "hls/abc");
+    }
+
+    #[test]
+    fn prefix_path_rejects_the_root_and_relative_segments() {
+        for prefix in ["", "/", "//", "hls//abc", "hls/./abc", "hls/..", "../etc"] {
+            assert!(matches!(prefix_path(prefix), Err(StorageError::InvalidPrefix(_))), "{:?}", prefix);
+        }
+    }
+}
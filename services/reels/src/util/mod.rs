@@ -1 +1,2 @@
 pub mod read_bytes;
+pub mod stream_to_file;
@@ -0,0 +1,163 @@
+use std::path::{Path, PathBuf};
+
+use actix_multipart::Field;
+use actix_web::web::Bytes;
+use futures_util::{Stream, StreamExt};
+use log::warn;
+use tokio::{fs::{self, File, OpenOptions}, io::{AsyncSeekExt, AsyncWriteExt}};
+use uuid::Uuid;
+
+use crate::error::{error::AppError, problem::ErrorCode};
+
+/// A multipart file written to the upload directory under a temporary name.
+/// The file is removed on drop, once its content has been handed to storage.
+pub struct TempUpload {
+    path: PathBuf,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: u64,
+}
+
+impl TempUpload {
+    /// Reserves a temporary file in `dir` for content produced on the server, such as generated images.
+    /// The path keeps the extension of `file_name` so tools can infer the output format from it.
+    pub fn reserve(dir: &Path, file_name: String, content_type: &str) -> Self {
+        let mut upload = TempUpload {
+            path: PathBuf::new(),
+            file_name,
+            content_type: content_type.to_string(),
+            size: 0,
+        };
+        upload.path = dir.join(format!(".{}{}", Uuid::new_v4(), upload.extension()));
+        upload
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn extension(&self) -> String {
+        PathBuf::from(&self.file_name)
+            .extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .map_or(String::new(), |ext| format!(".{}", ext))
+    }
+}
+
+impl Drop for TempUpload {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!("Failed to remove temporary upload {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+pub async fn stream_to_file(
+    field: &mut Field,
+    dir: &Path,
+    file_name: String,
+    max_size: u64,
+) -> Result<TempUpload, AppError> {
+    fs::create_dir_all(dir)
+        .await
+        .map_err(|e| AppError::InternalError(e.to_string()))?;
+
+    let path = dir.join(format!(".{}.part", Uuid::new_v4()));
+    let mut file = File::create(&path)
+        .await
+        .map_err(|e| AppError::InternalError(e.to_string()))?;
+
+    let content_type = field
+        .content_type()
+        .map_or("application/octet-stream".to_string(), |mime| mime.to_string());
+
+    let mut upload = TempUpload {
+        path,
+        file_name,
+        content_type,
+        size: 0,
+    };
+
+    while let Some(chunk) = field.next().await {
+        let chunk = chunk.map_err(|_| AppError::InternalError("Error reading multipart chunk".into()))?;
+
+        upload.size += chunk.len() as u64;
+        if upload.size > max_size {
+            return Err(AppError::PayloadTooLarge(ErrorCode::UploadTooLarge, format!(
+                "File exceeds the maximum upload size of {} bytes",
+                max_size
+            )));
+        }
+
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| AppError::InternalError(e.to_string()))?;
+
+    Ok(upload)
+}
+
+/// Appends a request body to the file at `path`, starting at `offset` and accepting at most `max_size` bytes.
+/// Returns how many bytes were written alongside the outcome, so the bytes received before
+/// a broken connection or an oversized chunk can still be kept.
+pub async fn append_to_file<S, E>(
+    body: &mut S,
+    path: &Path,
+    offset: u64,
+    max_size: u64,
+) -> (u64, Result<(), AppError>)
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+{
+    let mut written = 0;
+    let result = async {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .await
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+        // Anything past `offset` was written by a request that never got committed.
+        file.set_len(offset)
+            .await
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+        file.seek(std::io::SeekFrom::Start(offset))
+            .await
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+
+        let mut outcome = Ok(());
+        while let Some(chunk) = body.next().await {
+            let Ok(chunk) = chunk else {
+                outcome = Err(AppError::BadRequest(ErrorCode::UploadInterrupted, "Error reading request body".into()));
+                break;
+            };
+            if written + chunk.len() as u64 > max_size {
+                outcome = Err(AppError::PayloadTooLarge(ErrorCode::UploadTooLarge, format!(
+                    "Body exceeds the remaining upload length of {} bytes",
+                    max_size
+                )));
+                break;
+            }
+            if let Err(e) = file.write_all(&chunk).await {
+                outcome = Err(AppError::InternalError(e.to_string()));
+                break;
+            }
+            written += chunk.len() as u64;
+        }
+
+        file.sync_data()
+            .await
+            .map_err(|e| AppError::InternalError(e.to_string()))?;
+        outcome
+    }
+    .await;
+
+    (written, result)
+}